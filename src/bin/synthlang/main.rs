@@ -0,0 +1,595 @@
+//! Command line tools for exploring SynthLang languages.
+
+mod repl;
+
+use clap::{Parser, Subcommand, ValueEnum};
+use regex::Regex;
+use serde_json::{Map, Value};
+use std::fmt;
+use std::io::{self, Write};
+use std::ops::RangeInclusive;
+use std::path::PathBuf;
+use std::process;
+use std::sync::Arc;
+use synthlang::{
+    Blocklist, Gender, Lexicon, NameGenerator, SeedSearch, Similarity, SyllableType, SynthLang,
+    UniquenessTracker,
+};
+
+/// Like `println!`, but quietly exits when stdout is closed, e.g. when piped into `head`.
+macro_rules! outln {
+    ($($arg:tt)*) => {
+        out(format_args!("{}\n", format_args!($($arg)*)))
+    };
+}
+
+#[derive(Parser)]
+#[command(
+    name = "synthlang",
+    version,
+    about = "Generate and explore constructed languages"
+)]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+    /// Output format
+    #[arg(long, short, global = true, value_enum, default_value_t = Format::Text)]
+    format: Format,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum Format {
+    Text,
+    Json,
+    Csv,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Finds seeds whose languages match every given criterion
+    Search(SearchArgs),
+    /// Describes a language's sounds, syllables and grammar
+    Describe(LanguageArgs),
+    /// Generates words
+    Words(WordsArgs),
+    /// Compounds the words for two meanings
+    Compound(CompoundArgs),
+    /// Generates personal names
+    Names(NamesArgs),
+    /// Works with a language's dictionary
+    Lexicon {
+        #[command(subcommand)]
+        command: LexiconCommand,
+    },
+    /// Translates English text word by word
+    Translate(TranslateArgs),
+    /// Explores a language interactively
+    Repl(ReplArgs),
+}
+
+#[derive(Subcommand)]
+enum LexiconCommand {
+    /// Prints a word for every concept in the core vocabulary
+    Export(LanguageArgs),
+}
+
+#[derive(clap::Args)]
+struct LanguageArgs {
+    /// Seed of the language
+    #[arg(long, short, default_value_t = 0)]
+    seed: u64,
+}
+
+impl LanguageArgs {
+    fn lang(&self) -> SynthLang {
+        SynthLang::new(self.seed)
+    }
+}
+
+#[derive(clap::Args)]
+struct WordsArgs {
+    #[command(flatten)]
+    lang: LanguageArgs,
+    /// How many words to generate
+    #[arg(long, short = 'n', default_value_t = 20)]
+    count: usize,
+    /// Never repeat a word
+    #[arg(long)]
+    unique: bool,
+    /// Leave out profanity, slurs and trademarks
+    #[arg(long)]
+    filter: bool,
+    /// Leave out words that score below this for pronounceability, between 0 and 1
+    #[arg(long)]
+    min_pronounceability: Option<f32>,
+    /// Also print syllables and an IPA transcription
+    #[arg(long)]
+    details: bool,
+}
+
+#[derive(clap::Args)]
+struct CompoundArgs {
+    #[command(flatten)]
+    lang: LanguageArgs,
+    /// Meaning of the first part, such as `sky`
+    left: String,
+    /// Meaning of the second part, such as `fire`
+    right: String,
+    /// How many variations to generate
+    #[arg(long, short = 'n', default_value_t = 1)]
+    count: usize,
+}
+
+#[derive(clap::Args)]
+struct NamesArgs {
+    #[command(flatten)]
+    lang: LanguageArgs,
+    /// How many names to generate
+    #[arg(long, short = 'n', default_value_t = 20)]
+    count: usize,
+    /// Only generate names of this gender
+    #[arg(long, value_enum)]
+    gender: Option<GenderArg>,
+    /// Seed for picking names, so that the same language can produce different casts
+    #[arg(long, default_value_t = 0)]
+    names_seed: u64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum GenderArg {
+    Feminine,
+    Masculine,
+}
+
+#[derive(clap::Args)]
+struct TranslateArgs {
+    #[command(flatten)]
+    lang: LanguageArgs,
+    /// English text to translate
+    #[arg(required = true)]
+    text: Vec<String>,
+    /// Also print a word-by-word gloss
+    #[arg(long)]
+    gloss: bool,
+}
+
+#[derive(clap::Args)]
+struct ReplArgs {
+    #[command(flatten)]
+    lang: LanguageArgs,
+    /// Continue a session saved with `save`
+    #[arg(long, conflicts_with = "seed")]
+    load: Option<PathBuf>,
+}
+
+#[derive(clap::Args)]
+struct SearchArgs {
+    /// First seed to check
+    #[arg(long, default_value_t = 0)]
+    from: u64,
+    /// Seed to stop before
+    #[arg(long, default_value_t = u64::MAX)]
+    to: u64,
+    /// How many seeds to find
+    #[arg(long, short = 'n', default_value_t = 10)]
+    limit: usize,
+    /// Number of threads (default: one per CPU)
+    #[arg(long)]
+    threads: Option<usize>,
+    /// Phonemes the language must have, separated by commas
+    #[arg(long, value_delimiter = ',')]
+    has: Vec<String>,
+    /// Phonemes the language must not have, separated by commas
+    #[arg(long, value_delimiter = ',')]
+    lacks: Vec<String>,
+    /// Share of syllables with a shape, e.g. `cvc=0.5-1` or `vc=0`
+    #[arg(long = "syllables", value_parser = parse_share)]
+    syllables: Vec<(SyllableType, RangeInclusive<f64>)>,
+    /// Average word length in letters, e.g. `4-6`
+    #[arg(long, value_parser = parse_range)]
+    length: Option<RangeInclusive<f64>>,
+    /// Regex that sample words must all match, e.g. `^[a-z]+$`
+    #[arg(long)]
+    words: Option<Regex>,
+    /// How many sample words `--words` checks
+    #[arg(long, default_value_t = 20)]
+    samples: usize,
+    /// Seed of a language that results should sound like
+    #[arg(long)]
+    like: Option<u64>,
+    /// How far results may be from `--like`, between 0 and 1
+    #[arg(long, default_value_t = 0.5)]
+    max_distance: f64,
+}
+
+fn main() {
+    let cli = Cli::parse();
+    let format = cli.format;
+
+    match cli.command {
+        Command::Search(args) => search(format, args),
+        Command::Describe(args) => describe(format, &args),
+        Command::Words(args) => words(format, &args),
+        Command::Compound(args) => compound(format, &args),
+        Command::Names(args) => names(format, &args),
+        Command::Lexicon {
+            command: LexiconCommand::Export(args),
+        } => export(format, &args),
+        Command::Translate(args) => translate(format, &args),
+        Command::Repl(args) => {
+            let session = match &args.load {
+                Some(path) => repl::load(path).unwrap_or_else(|e| {
+                    eprintln!("error: {}", e);
+                    process::exit(1);
+                }),
+                None => repl::Session::new(args.lang.seed),
+            };
+            repl::run(session);
+        }
+    }
+}
+
+fn search(format: Format, args: SearchArgs) {
+    let mut search = SeedSearch::new(args.from..args.to);
+    if let Some(threads) = args.threads {
+        search.set_threads(threads);
+    }
+
+    let has: Vec<&str> = args.has.iter().map(String::as_str).collect();
+    search.require_phonemes(&has);
+    let lacks: Vec<&str> = args.lacks.iter().map(String::as_str).collect();
+    search.forbid_phonemes(&lacks);
+
+    for (syllable_type, share) in args.syllables {
+        search.require_syllable_share(syllable_type, share);
+    }
+    if let Some(length) = args.length {
+        search.require_word_length(length);
+    }
+    if let Some(words) = args.words {
+        search.require_words(args.samples, move |w| words.is_match(w));
+    }
+    if let Some(like) = args.like {
+        search.require_similar_to(SynthLang::new(like), args.max_distance);
+    }
+
+    let mut table = Table::new(format, &["seed", "sample"]);
+    for seed in search.run().take(args.limit) {
+        let mut lang = SynthLang::new(seed);
+        let words: Vec<Value> = (0..5).map(|_| lang.word().to_string().into()).collect();
+        table.row(vec![seed.into(), words.into()]);
+    }
+    table.finish();
+}
+
+fn describe(format: Format, args: &LanguageArgs) {
+    let mut lang = args.lang();
+    let grammar = lang.grammar();
+    let statistics = lang.statistics();
+    let strings = |v: &[String]| Value::from(v.to_vec());
+
+    let sample: Vec<Value> = (0..10).map(|_| lang.word().to_string().into()).collect();
+    let record = vec![
+        ("seed", lang.seed().into()),
+        ("consonants", strings(&lang.consonants)),
+        ("vowels", strings(&lang.vowels)),
+        ("cv_weight", lang.cv_weight.into()),
+        ("vc_weight", lang.vc_weight.into()),
+        ("cvc_weight", lang.cvc_weight.into()),
+        ("word_order", format!("{:?}", grammar.word_order).into()),
+        ("adjectives", format!("{:?}", grammar.adjective).into()),
+        ("adpositions", format!("{:?}", grammar.adposition).into()),
+        ("average_letters", round(statistics.expected_letters).into()),
+        (
+            "entropy_per_word",
+            round(statistics.entropy_per_word).into(),
+        ),
+        ("sample", sample.into()),
+    ];
+
+    print_record(format, record);
+}
+
+fn words(format: Format, args: &WordsArgs) {
+    let mut lang = args.lang.lang();
+    if args.unique {
+        lang.set_uniqueness_tracker(Some(UniquenessTracker::new(Similarity::Spelling, 1)));
+    }
+    if args.filter {
+        lang.set_content_filter(Some(Arc::new(Blocklist::default())));
+    }
+    lang.set_min_pronounceability(args.min_pronounceability);
+
+    let columns: &[&str] = if args.details {
+        &["word", "syllables", "ipa"]
+    } else {
+        &["word"]
+    };
+
+    let mut table = Table::new(format, columns);
+    for _ in 0..args.count {
+        let word = lang.word();
+        let mut row = vec![word.to_string().into()];
+        if args.details {
+            row.push(word.hyphenated().into());
+            row.push(word.ipa().into());
+        }
+        table.row(row);
+    }
+    table.finish();
+}
+
+fn compound(format: Format, args: &CompoundArgs) {
+    let mut lang = args.lang.lang();
+    let mut lexicon = Lexicon::new();
+    let left = lexicon.word(&lang, &args.left).clone();
+    let right = lexicon.word(&lang, &args.right).clone();
+
+    let mut table = Table::new(format, &["left", "right", "compound"]);
+    for _ in 0..args.count {
+        let compound = lang.compound(&left, &right);
+        table.row(vec![
+            left.to_string().into(),
+            right.to_string().into(),
+            compound.to_string().into(),
+        ]);
+    }
+    table.finish();
+}
+
+fn names(format: Format, args: &NamesArgs) {
+    let lang = args.lang.lang();
+    let casing = lang.casing();
+    let mut generator = NameGenerator::new(&lang, args.names_seed);
+
+    let mut table = Table::new(format, &["name", "given", "patronymic", "family"]);
+    for i in 0..args.count {
+        let gender = match args.gender {
+            Some(GenderArg::Feminine) => Gender::Feminine,
+            Some(GenderArg::Masculine) => Gender::Masculine,
+            None if i % 2 == 0 => Gender::Feminine,
+            None => Gender::Masculine,
+        };
+
+        let name = generator.name(gender);
+        let part = |w: &Option<synthlang::Word>| {
+            w.as_ref()
+                .map_or(String::new(), |w| casing.capitalize(&w.to_string()))
+        };
+        table.row(vec![
+            name.display(&casing).to_string().into(),
+            casing.capitalize(&name.given.to_string()).into(),
+            part(&name.patronymic).into(),
+            part(&name.family).into(),
+        ]);
+    }
+    table.finish();
+}
+
+fn export(format: Format, args: &LanguageArgs) {
+    let lexicon = args.lang().dictionary();
+
+    let mut table = Table::new(format, &["meaning", "word"]);
+    for (meaning, word) in lexicon.iter() {
+        table.row(vec![meaning.into(), word.to_string().into()]);
+    }
+    table.finish();
+}
+
+fn translate(format: Format, args: &TranslateArgs) {
+    let lang = args.lang.lang();
+    let translation = lang.translate_text(&args.text.join(" "));
+
+    match format {
+        Format::Text => {
+            outln!("{}", translation.text);
+            if args.gloss {
+                outln!("");
+                outln!("{}", translation.interlinear());
+            }
+        }
+        Format::Json => {
+            let words: Vec<Value> = translation
+                .words
+                .iter()
+                .map(|(word, gloss)| {
+                    let mut object = Map::new();
+                    object.insert("word".to_string(), word.as_str().into());
+                    object.insert("gloss".to_string(), gloss.as_str().into());
+                    Value::Object(object)
+                })
+                .collect();
+
+            print_record(
+                format,
+                vec![
+                    ("text", translation.text.into()),
+                    ("gloss", translation.gloss.into()),
+                    ("words", words.into()),
+                ],
+            );
+        }
+        Format::Csv => {
+            let mut table = Table::new(format, &["word", "gloss"]);
+            for (word, gloss) in translation.words {
+                table.row(vec![word.into(), gloss.into()]);
+            }
+            table.finish();
+        }
+    }
+}
+
+/// Prints rows as they're added: tab-separated text, a JSON array of objects or CSV with a
+/// header.
+struct Table<'a> {
+    format: Format,
+    columns: &'a [&'a str],
+    rows: usize,
+}
+
+impl<'a> Table<'a> {
+    fn new(format: Format, columns: &'a [&'a str]) -> Self {
+        match format {
+            Format::Text => {}
+            Format::Json => out(format_args!("[")),
+            Format::Csv => {
+                let header: Vec<String> = columns.iter().map(|c| csv(c)).collect();
+                outln!("{}", header.join(","));
+            }
+        }
+
+        Self {
+            format,
+            columns,
+            rows: 0,
+        }
+    }
+
+    fn row(&mut self, values: Vec<Value>) {
+        match self.format {
+            Format::Text => {
+                let values: Vec<String> = values.iter().map(text).collect();
+                outln!("{}", values.join("\t"));
+            }
+            Format::Json => {
+                let object: Map<String, Value> = self
+                    .columns
+                    .iter()
+                    .map(ToString::to_string)
+                    .zip(values)
+                    .collect();
+                let separator = if self.rows == 0 { "" } else { "," };
+                out(format_args!("{}\n  {}", separator, Value::Object(object)));
+            }
+            Format::Csv => {
+                let values: Vec<String> = values.iter().map(|v| csv(&text(v))).collect();
+                outln!("{}", values.join(","));
+            }
+        }
+
+        self.rows += 1;
+    }
+
+    fn finish(self) {
+        if self.format == Format::Json {
+            let end = if self.rows == 0 { "" } else { "\n" };
+            outln!("{}]", end);
+        }
+    }
+}
+
+/// Prints a single record: `key: value` lines, a JSON object or two-column CSV.
+fn print_record(format: Format, fields: Vec<(&str, Value)>) {
+    match format {
+        Format::Text => {
+            for (key, value) in fields {
+                outln!("{}: {}", key, text(&value));
+            }
+        }
+        Format::Json => {
+            let object: Map<String, Value> = fields
+                .into_iter()
+                .map(|(k, v)| (k.to_string(), v))
+                .collect();
+            outln!(
+                "{}",
+                serde_json::to_string_pretty(&Value::Object(object)).unwrap()
+            );
+        }
+        Format::Csv => {
+            outln!("key,value");
+            for (key, value) in fields {
+                outln!("{},{}", csv(key), csv(&text(&value)));
+            }
+        }
+    }
+}
+
+fn out(text: fmt::Arguments) {
+    let mut stdout = io::stdout().lock();
+    if stdout
+        .write_fmt(text)
+        .and_then(|()| stdout.flush())
+        .is_err()
+    {
+        process::exit(0);
+    }
+}
+
+/// Renders a value as plain text, with the items of lists separated by spaces.
+fn text(value: &Value) -> String {
+    match value {
+        Value::String(s) => s.clone(),
+        Value::Array(items) => items.iter().map(text).collect::<Vec<_>>().join(" "),
+        Value::Null => String::new(),
+        _ => value.to_string(),
+    }
+}
+
+/// Quotes a CSV field if it needs it.
+fn csv(field: &str) -> String {
+    if field.contains([',', '"', '\n']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+fn round(n: f64) -> f64 {
+    (n * 100.0).round() / 100.0
+}
+
+/// Parses `4-6` as `4.0..=6.0`, and `4` as `4.0..=4.0`.
+fn parse_range(s: &str) -> Result<RangeInclusive<f64>, String> {
+    let number = |n: &str| {
+        n.trim()
+            .parse::<f64>()
+            .map_err(|_| format!("invalid number `{}`", n))
+    };
+
+    match s.split_once('-') {
+        Some((min, max)) => Ok(number(min)?..=number(max)?),
+        None => {
+            let n = number(s)?;
+            Ok(n..=n)
+        }
+    }
+}
+
+/// Parses `cvc=0.5-1`.
+fn parse_share(s: &str) -> Result<(SyllableType, RangeInclusive<f64>), String> {
+    let (shape, share) = s
+        .split_once('=')
+        .ok_or_else(|| format!("expected `shape=share`, got `{}`", s))?;
+
+    let syllable_type = match shape.to_lowercase().as_str() {
+        "cv" => SyllableType::CV,
+        "vc" => SyllableType::VC,
+        "cvc" => SyllableType::CVC,
+        _ => return Err(format!("unknown syllable shape `{}`", shape)),
+    };
+
+    Ok((syllable_type, parse_range(share)?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ranges() {
+        assert_eq!(parse_range("4-6"), Ok(4.0..=6.0));
+        assert_eq!(parse_range("0.5"), Ok(0.5..=0.5));
+        assert!(parse_range("x").is_err());
+        assert_eq!(parse_share("CVC=0.5-1"), Ok((SyllableType::CVC, 0.5..=1.0)));
+        assert!(parse_share("ccv=1").is_err());
+    }
+
+    #[test]
+    fn formatting() {
+        assert_eq!(csv("plain"), "plain");
+        assert_eq!(csv("a,b"), "\"a,b\"");
+        assert_eq!(csv("say \"hi\""), "\"say \"\"hi\"\"\"");
+        assert_eq!(text(&Value::from(vec!["a", "b"])), "a b");
+    }
+}
@@ -0,0 +1,491 @@
+//! An interactive shell over a live language.
+//!
+//! A session is a seed and the commands that changed the language since, so undoing a command
+//! rebuilds the session from the seed without it, and a saved session is just those commands.
+
+use std::fs;
+use std::io::{self, BufRead, IsTerminal, Write};
+use std::path::Path;
+use synthlang::{Lexicon, SyllablePartType, SyllableType, SynthLang, Translator, Word};
+
+const HELP: &str = "\
+words [N]                   generate N words (default 10)
+compound A B                compound two words, given by number or by meaning
+pin MEANING WORD            give a meaning a word, by number or spelled out
+add PHONEME vowel|consonant add a phoneme to the inventory
+remove PHONEME              remove a phoneme from the inventory
+weight cv|vc|cvc N          set how often syllables take a shape
+weight PREVIOUS NEXT N      set how often NEXT follows PREVIOUS (^ for a syllable start)
+show                        describe the language
+list                        list the words generated so far
+lexicon                     list meanings and their words
+translate TEXT              translate English text with the lexicon
+history                     list the commands that made this session
+undo                        take back the last command that changed anything
+seed N                      start over with another language
+save FILE                   save the session
+load FILE                   replace the session with a saved one
+quit                        leave";
+
+enum Command {
+    Words(usize),
+    Compound(String, String),
+    Pin(String, String),
+    Add(String, SyllablePartType),
+    Remove(String),
+    SyllableWeight(SyllableType, i32),
+    TransitionWeight(String, String, i32),
+    Show,
+    List,
+    Lexicon,
+    Translate(String),
+    History,
+    Undo,
+    Seed(u64),
+    Save(String),
+    Load(String),
+    Help,
+    Quit,
+}
+
+impl Command {
+    fn parse(line: &str) -> Result<Self, String> {
+        let words: Vec<&str> = line.split_whitespace().collect();
+        let name = words.first().map(|w| w.to_lowercase()).unwrap_or_default();
+        let args = &words[1.min(words.len())..];
+
+        let command = match (name.as_str(), args) {
+            ("words", []) => Command::Words(10),
+            ("words", [count]) => Command::Words(number(count)?),
+            ("compound", [left, right]) => Command::Compound(left.to_string(), right.to_string()),
+            ("pin", [meaning, word]) => Command::Pin(meaning.to_string(), word.to_string()),
+            ("add", [phoneme, kind]) => {
+                let part_type = match kind.to_lowercase().as_str() {
+                    "vowel" | "v" => SyllablePartType::Vowel,
+                    "consonant" | "c" => SyllablePartType::Consonant,
+                    _ => return Err(format!("expected `vowel` or `consonant`, got `{}`", kind)),
+                };
+                Command::Add(phoneme.to_lowercase(), part_type)
+            }
+            ("remove", [phoneme]) => Command::Remove(phoneme.to_lowercase()),
+            ("weight", [shape, weight]) => {
+                let syllable_type = match shape.to_lowercase().as_str() {
+                    "cv" => SyllableType::CV,
+                    "vc" => SyllableType::VC,
+                    "cvc" => SyllableType::CVC,
+                    _ => return Err(format!("unknown syllable shape `{}`", shape)),
+                };
+                Command::SyllableWeight(syllable_type, number(weight)?)
+            }
+            ("weight", [previous, next, weight]) => {
+                let previous = if *previous == "^" {
+                    "\0".to_string()
+                } else {
+                    previous.to_lowercase()
+                };
+                Command::TransitionWeight(previous, next.to_lowercase(), number(weight)?)
+            }
+            ("show", []) => Command::Show,
+            ("list", []) => Command::List,
+            ("lexicon", []) => Command::Lexicon,
+            ("translate", [_, ..]) => Command::Translate(args.join(" ")),
+            ("history", []) => Command::History,
+            ("undo", []) => Command::Undo,
+            ("seed", [seed]) => Command::Seed(number(seed)?),
+            ("save", [path]) => Command::Save(path.to_string()),
+            ("load", [path]) => Command::Load(path.to_string()),
+            ("help", []) | ("?", []) => Command::Help,
+            ("quit", []) | ("exit", []) => Command::Quit,
+            ("", _) => return Err("empty command".to_string()),
+            (
+                "words" | "compound" | "pin" | "add" | "remove" | "weight" | "show" | "list"
+                | "lexicon" | "translate" | "history" | "undo" | "seed" | "save" | "load" | "help"
+                | "quit" | "exit",
+                _,
+            ) => return Err(format!("wrong arguments for `{}`, see `help`", name)),
+            _ => return Err(format!("unknown command `{}`, see `help`", name)),
+        };
+
+        Ok(command)
+    }
+
+    /// Whether the command changes the session, and so belongs in its history.
+    fn is_recorded(&self) -> bool {
+        matches!(
+            self,
+            Command::Words(_)
+                | Command::Compound(..)
+                | Command::Pin(..)
+                | Command::Add(..)
+                | Command::Remove(_)
+                | Command::SyllableWeight(..)
+                | Command::TransitionWeight(..)
+        )
+    }
+}
+
+fn number<T: std::str::FromStr>(s: &str) -> Result<T, String> {
+    s.parse().map_err(|_| format!("invalid number `{}`", s))
+}
+
+/// A language, the words generated from it so far and a lexicon.
+pub struct Session {
+    seed: u64,
+    history: Vec<String>,
+    lang: SynthLang,
+    lexicon: Lexicon,
+    results: Vec<Word>,
+}
+
+impl Session {
+    pub fn new(seed: u64) -> Self {
+        Self {
+            seed,
+            history: vec![],
+            lang: SynthLang::new(seed),
+            lexicon: Lexicon::new(),
+            results: vec![],
+        }
+    }
+
+    /// Rebuilds a session from the commands `script()` wrote.
+    pub fn from_script(script: &str) -> Result<Self, String> {
+        let mut session = Session::new(0);
+        for (i, line) in script.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let command = Command::parse(line).map_err(|e| format!("line {}: {}", i + 1, e))?;
+            if !command.is_recorded() && !matches!(command, Command::Seed(_)) {
+                return Err(format!("line {}: `{}` can't be replayed", i + 1, line));
+            }
+            session
+                .execute(line, command)
+                .map_err(|e| format!("line {}: {}", i + 1, e))?;
+        }
+
+        Ok(session)
+    }
+
+    /// The seed and every recorded command, one per line.
+    pub fn script(&self) -> String {
+        let mut script = format!("# synthlang session\nseed {}\n", self.seed);
+        for line in &self.history {
+            script.push_str(line);
+            script.push('\n');
+        }
+
+        script
+    }
+
+    /// Runs one line of input. Returns what to print, or `None` to quit.
+    pub fn run(&mut self, line: &str) -> Result<Option<String>, String> {
+        let line = line.trim();
+        let command = Command::parse(line)?;
+
+        match command {
+            Command::Quit => Ok(None),
+            Command::Save(path) => {
+                fs::write(&path, self.script()).map_err(|e| format!("{}: {}", path, e))?;
+                Ok(Some(format!(
+                    "saved {} commands to {}",
+                    self.history.len(),
+                    path
+                )))
+            }
+            Command::Load(path) => {
+                *self = load(Path::new(&path))?;
+                Ok(Some(format!(
+                    "loaded {} commands from {}",
+                    self.history.len(),
+                    path
+                )))
+            }
+            command => self.execute(line, command).map(Some),
+        }
+    }
+
+    fn execute(&mut self, line: &str, command: Command) -> Result<String, String> {
+        let recorded = command.is_recorded();
+        let output = match command {
+            Command::Words(count) => {
+                let words: Vec<Word> = (0..count).map(|_| self.lang.word()).collect();
+                self.push_results(words)
+            }
+            Command::Compound(left, right) => {
+                let left = self.resolve(&left)?;
+                let right = self.resolve(&right)?;
+                let compound = self.lang.compound(&left, &right);
+                self.push_results(vec![compound])
+            }
+            Command::Pin(meaning, word) => {
+                let word = match self.result(&word) {
+                    Some(word) => word?,
+                    None => self.lang.parse(&word).map_err(|e| e.to_string())?,
+                };
+                let mut output = format!("{} = {}", meaning, word);
+                if let Some(illegal) = self.lang.illegal_transitions_in(&word).first() {
+                    output.push_str(&format!(" ({})", illegal));
+                }
+                self.lexicon.pin(&meaning, word);
+                output
+            }
+            Command::Add(phoneme, part_type) => {
+                if !self.lang.add_phoneme(&phoneme, part_type) {
+                    return Err(format!("the language already has `{}`", phoneme));
+                }
+                format!("added {}", phoneme)
+            }
+            Command::Remove(phoneme) => {
+                if !self.lang.remove_phoneme(&phoneme) {
+                    return Err(format!(
+                        "`{}` isn't in the inventory, or it's the last of its kind",
+                        phoneme
+                    ));
+                }
+                format!("removed {}", phoneme)
+            }
+            Command::SyllableWeight(syllable_type, weight) => {
+                self.set_syllable_weight(syllable_type, weight)?;
+                self.syllable_weights()
+            }
+            Command::TransitionWeight(previous, next, weight) => {
+                if !self.lang.set_transition_weight(&previous, &next, weight) {
+                    return Err(format!(
+                        "can't weight `{}` after `{}`",
+                        next,
+                        previous.replace('\0', "^")
+                    ));
+                }
+                format!("{} -> {}: {}", previous.replace('\0', "^"), next, weight)
+            }
+            Command::Show => self.describe(),
+            Command::List => self
+                .results
+                .iter()
+                .enumerate()
+                .map(|(i, w)| format!("#{}\t{}\t{}\t{}", i + 1, w, w.hyphenated(), w.ipa()))
+                .collect::<Vec<_>>()
+                .join("\n"),
+            Command::Lexicon => self
+                .lexicon
+                .iter()
+                .map(|(meaning, word)| format!("{}\t{}", meaning, word))
+                .collect::<Vec<_>>()
+                .join("\n"),
+            Command::Translate(text) => {
+                let mut translator = Translator::with_lexicon(&self.lang, self.lexicon.clone());
+                let translation = translator.translate(&text);
+                format!("{}\n\n{}", translation.text, translation.interlinear())
+            }
+            Command::History => self.history.join("\n"),
+            Command::Undo => {
+                let mut history = self.history.clone();
+                let undone = history.pop().ok_or("nothing to undo")?;
+                self.replay(&history)?;
+                format!("undid `{}`", undone)
+            }
+            Command::Seed(seed) => {
+                *self = Session::new(seed);
+                format!("started over with seed {}", seed)
+            }
+            Command::Help => HELP.to_string(),
+            Command::Save(_) | Command::Load(_) | Command::Quit => String::new(),
+        };
+
+        if recorded {
+            self.history.push(line.to_string());
+        }
+
+        Ok(output)
+    }
+
+    /// Replaces the session with a fresh one from the same seed after `history`.
+    fn replay(&mut self, history: &[String]) -> Result<(), String> {
+        let mut session = Session::new(self.seed);
+        for line in history {
+            let command = Command::parse(line)?;
+            session.execute(line, command)?;
+        }
+
+        *self = session;
+        Ok(())
+    }
+
+    fn push_results(&mut self, words: Vec<Word>) -> String {
+        let lines: Vec<String> = words
+            .iter()
+            .enumerate()
+            .map(|(i, w)| format!("#{}\t{}", self.results.len() + i + 1, w))
+            .collect();
+        self.results.extend(words);
+
+        lines.join("\n")
+    }
+
+    /// The generated word numbered `arg`, if `arg` is a number like `3` or `#3`.
+    fn result(&self, arg: &str) -> Option<Result<Word, String>> {
+        let index: usize = arg.trim_start_matches('#').parse().ok()?;
+
+        Some(
+            index
+                .checked_sub(1)
+                .and_then(|i| self.results.get(i))
+                .cloned()
+                .ok_or_else(|| format!("there's no word #{}", index)),
+        )
+    }
+
+    /// A generated word by number, or the lexicon's word for a meaning.
+    fn resolve(&mut self, arg: &str) -> Result<Word, String> {
+        match self.result(arg) {
+            Some(word) => word,
+            None => Ok(self.lexicon.word(&self.lang, arg).clone()),
+        }
+    }
+
+    fn set_syllable_weight(
+        &mut self,
+        syllable_type: SyllableType,
+        weight: i32,
+    ) -> Result<(), String> {
+        if weight < 0 {
+            return Err("weights can't be negative".to_string());
+        }
+
+        let lang = &mut self.lang;
+        let others = match syllable_type {
+            SyllableType::CV => lang.vc_weight + lang.cvc_weight,
+            SyllableType::VC => lang.cv_weight + lang.cvc_weight,
+            SyllableType::CVC => lang.cv_weight + lang.vc_weight,
+        };
+        if others + weight == 0 {
+            return Err("at least one syllable shape needs a weight".to_string());
+        }
+
+        match syllable_type {
+            SyllableType::CV => lang.cv_weight = weight,
+            SyllableType::VC => lang.vc_weight = weight,
+            SyllableType::CVC => lang.cvc_weight = weight,
+        }
+        Ok(())
+    }
+
+    fn syllable_weights(&self) -> String {
+        format!(
+            "cv {}, vc {}, cvc {}",
+            self.lang.cv_weight, self.lang.vc_weight, self.lang.cvc_weight
+        )
+    }
+
+    fn describe(&self) -> String {
+        let statistics = self.lang.statistics();
+
+        format!(
+            "seed: {}\nconsonants: {}\nvowels: {}\nsyllables: {}\naverage letters: {:.2}\nwords: {}, meanings: {}",
+            self.seed,
+            self.lang.consonants.join(" "),
+            self.lang.vowels.join(" "),
+            self.syllable_weights(),
+            statistics.expected_letters,
+            self.results.len(),
+            self.lexicon.len(),
+        )
+    }
+}
+
+/// Reads commands from stdin until `quit` or the end of input. Prompts are only shown when
+/// stdin is a terminal, so scripts can be piped in.
+pub fn run(mut session: Session) {
+    let stdin = io::stdin();
+    let interactive = stdin.is_terminal();
+    if interactive {
+        println!("Type `help` for a list of commands.");
+    }
+
+    let mut lines = stdin.lock().lines();
+    loop {
+        if interactive {
+            print!("synthlang> ");
+            let _ = io::stdout().flush();
+        }
+
+        let line = match lines.next() {
+            Some(Ok(line)) => line,
+            _ => break,
+        };
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        match session.run(&line) {
+            Ok(Some(output)) if output.is_empty() => {}
+            Ok(Some(output)) => println!("{}", output),
+            Ok(None) => break,
+            Err(e) => eprintln!("error: {}", e),
+        }
+    }
+}
+
+/// Starts a session from a saved file.
+pub fn load(path: &Path) -> Result<Session, String> {
+    let script = fs::read_to_string(path).map_err(|e| format!("{}: {}", path.display(), e))?;
+    Session::from_script(&script).map_err(|e| format!("{}, {}", path.display(), e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run(session: &mut Session, line: &str) -> String {
+        session.run(line).unwrap().unwrap()
+    }
+
+    #[test]
+    fn undo() {
+        let mut session = Session::new(5);
+        run(&mut session, "words 3");
+        run(&mut session, "compound 1 #2");
+        let before = run(&mut session, "list");
+
+        let vowel = session.lang.vowels[0].clone();
+        run(&mut session, &format!("remove {}", vowel));
+        run(&mut session, "weight cvc 0");
+        run(&mut session, "words 20");
+        run(&mut session, "undo");
+        run(&mut session, "undo");
+        run(&mut session, "undo");
+
+        assert_eq!(run(&mut session, "list"), before);
+        assert_eq!(run(&mut session, "history"), "words 3\ncompound 1 #2");
+        assert!(session.run("compound 1 9").is_err());
+        assert!(session.run("weight vc 1").is_ok());
+        assert!(session.run("weight cv 0").is_ok());
+        assert!(session.run("weight cvc 0").is_ok());
+        assert!(session.run("weight vc 0").is_err());
+        assert!(session.run("frobnicate").is_err());
+        assert!(session.run("words many").is_err());
+    }
+
+    #[test]
+    fn script() {
+        let mut session = Session::new(8);
+        run(&mut session, "words 4");
+        run(&mut session, "pin moon #2");
+        run(&mut session, "add ʘ consonant");
+        run(&mut session, "weight ^ ʘ 50");
+        run(&mut session, "compound moon sun");
+        run(&mut session, "translate the moon");
+
+        let mut loaded = Session::from_script(&session.script()).unwrap();
+        assert_eq!(loaded.script(), session.script());
+        for line in &["list", "lexicon", "show"] {
+            assert_eq!(run(&mut loaded, line), run(&mut session, line));
+        }
+
+        assert!(Session::from_script("seed 1\nsave x").is_err());
+        assert!(Session::from_script("seed 1\nremove ʘ").is_err());
+    }
+}
@@ -0,0 +1,300 @@
+//! Loanwords: adapting a word from another language to this language's sounds, syllable
+//! shapes and transitions.
+
+use crate::phoneme;
+use crate::{Syllable, SyllablePart, SyllablePartType, SynthLang, Word};
+
+// Costs used when forcing a foreign syllable into one of our shapes. Inserting a vowel is the
+// cheapest repair, so "tak" in a CV-only language becomes "ta.ka" rather than "ta".
+const INSERT_VOWEL: u32 = 1;
+const INSERT_CONSONANT: u32 = 2;
+const DELETE_CONSONANT: u32 = 2;
+const DELETE_VOWEL: u32 = 3;
+
+enum Slot<'a> {
+    Keep(&'a SyllablePart),
+    Insert(SyllablePartType),
+}
+
+impl SynthLang {
+    /// Adapts a word borrowed from another language. Foreign phonemes are replaced with the
+    /// nearest phoneme in this language, syllables are repaired into shapes this language
+    /// allows, and phonemes that this language never puts next to each other are swapped out.
+    #[must_use]
+    pub fn borrow(&self, word: &Word, from: &SynthLang) -> Word {
+        let mapped: Vec<SyllablePart> = word
+            .parts
+            .iter()
+            .flat_map(|s| s.parts.iter())
+            .map(|part| self.nearest(part, from))
+            .collect();
+
+        // Words that already have acceptable shapes keep their syllable boundaries
+        let templates = self.templates();
+        let fits = word.parts.iter().all(|s| {
+            templates.iter().any(|t| {
+                t.len() == s.parts.len() && t.iter().zip(&s.parts).all(|(a, b)| *a == b.part_type)
+            })
+        });
+
+        let syllables = if fits {
+            let mut mapped = mapped.into_iter();
+            word.parts
+                .iter()
+                .map(|s| Syllable {
+                    parts: mapped.by_ref().take(s.parts.len()).collect(),
+                })
+                .collect()
+        } else {
+            self.syllabify(&mapped)
+        };
+
+        Word::new(self.repair_transitions(syllables), word.compound_rule)
+    }
+
+    fn nearest(&self, part: &SyllablePart, from: &SynthLang) -> SyllablePart {
+        let candidates = match part.part_type {
+            SyllablePartType::Vowel => &self.vowels,
+            SyllablePartType::Consonant => &self.consonants,
+        };
+
+        if candidates.contains(&part.value) {
+            return part.clone();
+        }
+
+        let features = from
+            .features(&part.value)
+            .or_else(|| phoneme::features(&part.value));
+
+        let value = match features {
+            Some(features) => candidates.iter().min_by_key(|c| {
+                phoneme::features(c).map_or(u32::MAX, |f| phoneme::distance(features, f))
+            }),
+            None => candidates
+                .iter()
+                .find(|c| c.chars().next() == part.value.chars().next())
+                .or_else(|| candidates.first()),
+        };
+
+        SyllablePart {
+            part_type: part.part_type,
+            value: value.unwrap_or(&part.value).clone(),
+        }
+    }
+
+    pub(crate) fn templates(&self) -> Vec<Vec<SyllablePartType>> {
+        use SyllablePartType::{Consonant, Vowel};
+
+        let mut templates = vec![];
+        if self.cv_weight > 0 {
+            templates.push(vec![Consonant, Vowel]);
+        }
+        if self.vc_weight > 0 {
+            templates.push(vec![Vowel, Consonant]);
+        }
+        if self.cvc_weight > 0 {
+            templates.push(vec![Consonant, Vowel, Consonant]);
+        }
+
+        templates
+    }
+
+    /// Splits `parts` into syllables of allowed shapes with the fewest insertions and deletions.
+    fn syllabify(&self, parts: &[SyllablePart]) -> Vec<Syllable> {
+        let templates = self.templates();
+
+        // best[i] is the cheapest way to syllabify parts[..i]
+        let mut best: Vec<Option<(u32, usize, usize)>> = vec![None; parts.len() + 1];
+        best[0] = Some((0, 0, 0));
+
+        for i in 0..parts.len() {
+            let cost = match best[i] {
+                Some((cost, _, _)) => cost,
+                None => continue,
+            };
+
+            for j in i + 1..=(i + 5).min(parts.len()) {
+                for (t, template) in templates.iter().enumerate() {
+                    let (c, _) = Self::align(&parts[i..j], template);
+                    if best[j].map_or(true, |(b, _, _)| cost + c < b) {
+                        best[j] = Some((cost + c, i, t));
+                    }
+                }
+            }
+        }
+
+        let mut syllables = vec![];
+        let mut j = parts.len();
+        while j > 0 {
+            let (_, i, t) = best[j].unwrap();
+            let (_, slots) = Self::align(&parts[i..j], &templates[t]);
+
+            let parts = slots
+                .into_iter()
+                .map(|slot| match slot {
+                    Slot::Keep(part) => part.clone(),
+                    Slot::Insert(part_type) => SyllablePart {
+                        value: self.epenthetic(&part_type),
+                        part_type,
+                    },
+                })
+                .collect();
+
+            syllables.push(Syllable { parts });
+            j = i;
+        }
+        syllables.reverse();
+
+        syllables
+    }
+
+    /// Aligns a run of phonemes with a syllable template, returning the repair cost and the
+    /// resulting slots.
+    fn align<'a>(parts: &'a [SyllablePart], template: &[SyllablePartType]) -> (u32, Vec<Slot<'a>>) {
+        let delete = |p: &SyllablePart| match p.part_type {
+            SyllablePartType::Vowel => DELETE_VOWEL,
+            SyllablePartType::Consonant => DELETE_CONSONANT,
+        };
+        let insert = |t: &SyllablePartType| match t {
+            SyllablePartType::Vowel => INSERT_VOWEL,
+            SyllablePartType::Consonant => INSERT_CONSONANT,
+        };
+
+        // Plain edit distance without substitution
+        let mut cost = vec![vec![0; template.len() + 1]; parts.len() + 1];
+        for i in 1..=parts.len() {
+            cost[i][0] = cost[i - 1][0] + delete(&parts[i - 1]);
+        }
+        for j in 1..=template.len() {
+            cost[0][j] = cost[0][j - 1] + insert(&template[j - 1]);
+        }
+        for i in 1..=parts.len() {
+            for j in 1..=template.len() {
+                let mut c = (cost[i - 1][j] + delete(&parts[i - 1]))
+                    .min(cost[i][j - 1] + insert(&template[j - 1]));
+                if parts[i - 1].part_type == template[j - 1] {
+                    c = c.min(cost[i - 1][j - 1]);
+                }
+                cost[i][j] = c;
+            }
+        }
+
+        let mut slots = vec![];
+        let (mut i, mut j) = (parts.len(), template.len());
+        while i > 0 || j > 0 {
+            if i > 0
+                && j > 0
+                && parts[i - 1].part_type == template[j - 1]
+                && cost[i][j] == cost[i - 1][j - 1]
+            {
+                slots.push(Slot::Keep(&parts[i - 1]));
+                i -= 1;
+                j -= 1;
+            } else if j > 0 && cost[i][j] == cost[i][j - 1] + insert(&template[j - 1]) {
+                slots.push(Slot::Insert(template[j - 1]));
+                j -= 1;
+            } else {
+                i -= 1;
+            }
+        }
+        slots.reverse();
+
+        (cost[parts.len()][template.len()], slots)
+    }
+
+    /// The phoneme this language reaches for when it needs to fill a gap: whichever is most
+    /// likely at the start of a syllable.
+    fn epenthetic(&self, part_type: &SyllablePartType) -> String {
+        let candidates = match part_type {
+            SyllablePartType::Vowel => &self.vowels,
+            SyllablePartType::Consonant => &self.consonants,
+        };
+
+        candidates
+            .iter()
+            .rev()
+            .max_by_key(|c| self.weight("\0", c))
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    /// Replaces phonemes that this language never generates after the preceding phoneme.
+    fn repair_transitions(&self, mut syllables: Vec<Syllable>) -> Vec<Syllable> {
+        for syllable in &mut syllables {
+            let mut prev = "\0".to_string();
+
+            for part in &mut syllable.parts {
+                if self.weight(&prev, &part.value) == 0 {
+                    let candidates = match part.part_type {
+                        SyllablePartType::Vowel => &self.vowels,
+                        SyllablePartType::Consonant => &self.consonants,
+                    };
+                    let features = phoneme::features(&part.value);
+
+                    let replacement = candidates
+                        .iter()
+                        .filter(|c| self.weight(&prev, c) > 0)
+                        .min_by_key(|c| match (features, phoneme::features(c)) {
+                            (Some(a), Some(b)) => phoneme::distance(a, b),
+                            _ => u32::MAX,
+                        });
+
+                    if let Some(replacement) = replacement {
+                        part.value = replacement.clone();
+                    }
+                }
+
+                prev = part.value.clone();
+            }
+        }
+
+        syllables
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn borrowed_words_fit() {
+        for seed in 0..50 {
+            let mut lender = SynthLang::new(seed);
+            let borrower = SynthLang::new(seed + 1000);
+            let templates = borrower.templates();
+
+            for _ in 0..20 {
+                let word = lender.word();
+                let borrowed = borrower.borrow(&word, &lender);
+
+                for syllable in &borrowed.parts {
+                    let shape: Vec<SyllablePartType> =
+                        syllable.parts.iter().map(|p| p.part_type).collect();
+                    assert!(templates.iter().any(
+                        |t| t.len() == shape.len() && t.iter().zip(&shape).all(|(a, b)| a == b)
+                    ));
+
+                    let mut prev = "\0";
+                    for part in &syllable.parts {
+                        assert!(
+                            borrower.features(&part.value).is_some()
+                                || phoneme::features(&part.value).is_none()
+                        );
+                        assert!(borrower.weight(prev, &part.value) > 0);
+                        prev = &part.value;
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn native_words_are_unchanged() {
+        let mut lang = SynthLang::new(1);
+
+        for _ in 0..20 {
+            let word = lang.word();
+            assert_eq!(lang.borrow(&word, &lang).to_string(), word.to_string());
+        }
+    }
+}
@@ -0,0 +1,226 @@
+//! Capitalization that knows about a language's spelling: multi-letter graphemes, leading
+//! apostrophes and lowercase particles in names.
+
+use crate::{Lexicon, SynthLang, Word};
+use rand::Rng;
+use std::sync::OnceLock;
+
+// Meanings whose words stay lowercase inside names, like "of" in "Aldric of Greenhill"
+const PARTICLES: &[&str] = &["of", "the", "and"];
+
+/// How the first grapheme of a word is capitalized when it's spelled with several letters.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DigraphCase {
+    /// Only the first letter, as in "Shira".
+    First,
+    /// The whole grapheme, as in Dutch "IJssel".
+    All,
+}
+
+/// A language's capitalization conventions.
+#[derive(Debug, Clone)]
+pub struct Casing {
+    pub digraphs: DigraphCase,
+    /// Words that stay lowercase in titles and names unless they come first.
+    pub particles: Vec<String>,
+    /// Graphemes spelled with more than one letter.
+    pub multigraphs: Vec<String>,
+}
+
+impl Default for Casing {
+    fn default() -> Self {
+        Self {
+            digraphs: DigraphCase::First,
+            particles: PARTICLES.iter().map(ToString::to_string).collect(),
+            multigraphs: vec![],
+        }
+    }
+}
+
+impl Casing {
+    /// Capitalizes the first letter of `word`, skipping anything before it that isn't a
+    /// letter (such as a glottal stop written `'`).
+    #[must_use]
+    pub fn capitalize(&self, word: &str) -> String {
+        let start = match word.char_indices().find(|(_, c)| c.is_alphabetic()) {
+            Some((i, _)) => i,
+            None => return word.to_string(),
+        };
+        let (before, rest) = word.split_at(start);
+
+        let multigraph = self
+            .multigraphs
+            .iter()
+            .filter(|m| rest.starts_with(m.as_str()))
+            .max_by_key(|m| m.len());
+
+        let mut capitalized = before.to_string();
+        match multigraph {
+            Some(m) if self.digraphs == DigraphCase::All => {
+                capitalized.push_str(&m.to_uppercase());
+                capitalized.push_str(&rest[m.len()..]);
+            }
+            _ => {
+                let mut chars = rest.chars();
+                if let Some(first) = chars.next() {
+                    capitalized.push_str(&titlecase(first));
+                }
+                capitalized.extend(chars);
+            }
+        }
+
+        capitalized
+    }
+
+    /// Capitalizes every word of `text` except particles that don't come first. Words are
+    /// separated by spaces or hyphens.
+    #[must_use]
+    pub fn title_case(&self, text: &str) -> String {
+        let mut titled = String::new();
+        let mut first = true;
+
+        for piece in text.split_inclusive([' ', '-']) {
+            let word = piece.trim_end_matches([' ', '-']).to_lowercase();
+            let separator = &piece[piece.trim_end_matches([' ', '-']).len()..];
+
+            if word.is_empty() {
+                titled.push_str(separator);
+                continue;
+            }
+
+            if !first && self.particles.contains(&word) {
+                titled.push_str(&word);
+            } else {
+                titled.push_str(&self.capitalize(&word));
+            }
+            titled.push_str(separator);
+            first = false;
+        }
+
+        titled
+    }
+}
+
+/// The titlecase form of `c`, which differs from its uppercase form for a few letters.
+fn titlecase(c: char) -> String {
+    match c {
+        'ǆ' | 'ǅ' | 'Ǆ' => "ǅ".to_string(),
+        'ǉ' | 'ǈ' | 'Ǉ' => "ǈ".to_string(),
+        'ǌ' | 'ǋ' | 'Ǌ' => "ǋ".to_string(),
+        'ǳ' | 'ǲ' | 'Ǳ' => "ǲ".to_string(),
+        'ß' => "Ss".to_string(),
+        _ => c.to_uppercase().collect(),
+    }
+}
+
+impl SynthLang {
+    /// This language's capitalization conventions. Its particles are its own words for
+    /// "of", "the" and "and", along with the English ones.
+    ///
+    /// They're worked out once and kept until a setter changes the language's words. Editing
+    /// its public fields directly doesn't clear them.
+    #[must_use]
+    pub fn casing(&self) -> Casing {
+        self.cached_casing().clone()
+    }
+
+    pub(crate) fn cached_casing(&self) -> &Casing {
+        self.casing.get_or_init(|| self.build_casing())
+    }
+
+    pub(crate) fn clear_casing(&mut self) {
+        self.casing = OnceLock::new();
+    }
+
+    fn build_casing(&self) -> Casing {
+        let digraphs = match &self.parent {
+            Some(parent) => parent.casing().digraphs,
+            None => {
+                let mut rng = self.keyed_rng(Self::hash_key("\0casing"));
+                if rng.gen_bool(0.1) {
+                    DigraphCase::All
+                } else {
+                    DigraphCase::First
+                }
+            }
+        };
+
+        let mut lexicon = Lexicon::new();
+        let mut particles = Casing::default().particles;
+        for particle in PARTICLES {
+            particles.push(lexicon.word(self, particle).to_string());
+        }
+
+        let multigraphs = self
+            .consonants
+            .iter()
+            .chain(self.vowels.iter())
+            .filter(|p| p.chars().count() > 1)
+            .cloned()
+            .collect();
+
+        Casing {
+            digraphs,
+            particles,
+            multigraphs,
+        }
+    }
+
+    /// Spells `word` with a capital letter, following this language's conventions.
+    #[must_use]
+    pub fn capitalize(&self, word: &Word) -> String {
+        self.cached_casing().capitalize(&word.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn capitalize() {
+        let casing = Casing {
+            digraphs: DigraphCase::All,
+            multigraphs: vec!["zh".to_string(), "ij".to_string()],
+            ..Casing::default()
+        };
+        assert_eq!(casing.capitalize("ijssel"), "IJssel");
+        assert_eq!(casing.capitalize("zhao"), "ZHao");
+        assert_eq!(casing.capitalize("'okina"), "'Okina");
+        assert_eq!(casing.capitalize("йаш"), "Йаш");
+        assert_eq!(casing.capitalize("ǆam"), "ǅam");
+
+        let casing = Casing {
+            multigraphs: vec!["zh".to_string()],
+            ..Casing::default()
+        };
+        assert_eq!(casing.capitalize("zhao"), "Zhao");
+        assert_eq!(casing.capitalize("ta'ek"), "Ta'ek");
+    }
+
+    #[test]
+    fn cached() {
+        let mut lang = SynthLang::new(4);
+        assert!(!lang.casing().multigraphs.contains(&"ʘh".to_string()));
+        lang.add_phoneme("ʘh", crate::SyllablePartType::Consonant);
+        assert!(lang.casing().multigraphs.contains(&"ʘh".to_string()));
+    }
+
+    #[test]
+    fn title_case() {
+        let casing = Casing::default();
+        assert_eq!(
+            casing.title_case("aldric of green-hill"),
+            "Aldric of Green-Hill"
+        );
+        assert_eq!(casing.title_case("the  ford"), "The  Ford");
+
+        let lang = SynthLang::new(4);
+        let of = lang.word_for("of").to_string();
+        let casing = lang.casing();
+        assert_eq!(
+            casing.title_case(&format!("aldric {} ra", of)),
+            format!("Aldric {} Ra", of)
+        );
+    }
+}
@@ -0,0 +1,171 @@
+//! How different two languages sound, from their inventories, weights and basic words.
+
+use crate::unique::edit_distance;
+use crate::vocabulary::CORE_VOCABULARY;
+use crate::SynthLang;
+use std::collections::{HashMap, HashSet};
+
+// How much each part counts towards the distance between two languages
+const INVENTORY_WEIGHT: f64 = 0.3;
+const TRANSITION_WEIGHT: f64 = 0.25;
+const SYLLABLE_WEIGHT: f64 = 0.15;
+const LEXICON_WEIGHT: f64 = 0.3;
+
+impl SynthLang {
+    /// How different this language is from `other`, between 0 (the same) and 1. It combines
+    /// how many phonemes they don't share, how differently they order them (the
+    /// Jensen-Shannon divergence between their transition weights), how differently they
+    /// shape syllables, and how far apart their words for the basic vocabulary are.
+    #[must_use]
+    pub fn distance(&self, other: &SynthLang) -> f64 {
+        INVENTORY_WEIGHT * self.inventory_distance(other)
+            + TRANSITION_WEIGHT * self.transition_distance(other)
+            + SYLLABLE_WEIGHT * self.syllable_distance(other)
+            + LEXICON_WEIGHT * self.lexicon_distance(other)
+    }
+
+    /// One minus the Jaccard similarity of the two inventories.
+    fn inventory_distance(&self, other: &SynthLang) -> f64 {
+        let inventory = |lang: &SynthLang| -> HashSet<String> {
+            lang.consonants
+                .iter()
+                .chain(lang.vowels.iter())
+                .cloned()
+                .collect()
+        };
+        let (a, b) = (inventory(self), inventory(other));
+
+        1.0 - a.intersection(&b).count() as f64 / a.union(&b).count().max(1) as f64
+    }
+
+    /// The Jensen-Shannon divergence between what follows each phoneme (and the start of a
+    /// syllable), averaged over every phoneme in either language. Phonemes that only one
+    /// language has count as completely different.
+    fn transition_distance(&self, other: &SynthLang) -> f64 {
+        let contexts: HashSet<&String> = self
+            .next_part_weights
+            .keys()
+            .chain(other.next_part_weights.keys())
+            .collect();
+
+        let total: f64 = contexts
+            .iter()
+            .map(|context| {
+                match (
+                    self.next_part_weights.get(*context),
+                    other.next_part_weights.get(*context),
+                ) {
+                    (Some(a), Some(b)) => {
+                        (jensen_shannon(&a.0, &b.0) + jensen_shannon(&a.1, &b.1)) / 2.0
+                    }
+                    _ => 1.0,
+                }
+            })
+            .sum();
+
+        total / contexts.len().max(1) as f64
+    }
+
+    fn syllable_distance(&self, other: &SynthLang) -> f64 {
+        let weights = |lang: &SynthLang| {
+            vec![
+                ("cv".to_string(), lang.cv_weight),
+                ("vc".to_string(), lang.vc_weight),
+                ("cvc".to_string(), lang.cvc_weight),
+            ]
+        };
+
+        jensen_shannon(&weights(self), &weights(other))
+    }
+
+    /// The average edit distance between the two languages' words for basic concepts, relative
+    /// to the length of the longer word.
+    fn lexicon_distance(&self, other: &SynthLang) -> f64 {
+        let letters = |lang: &SynthLang, concept| -> Vec<String> {
+            lang.word_for_concept(concept)
+                .to_string()
+                .chars()
+                .map(String::from)
+                .collect()
+        };
+
+        let basic: Vec<_> = CORE_VOCABULARY.iter().filter(|c| c.basic).collect();
+        let total: f64 = basic
+            .iter()
+            .map(|concept| {
+                let (a, b) = (letters(self, concept), letters(other, concept));
+                edit_distance(&a, &b) as f64 / a.len().max(b.len()).max(1) as f64
+            })
+            .sum();
+
+        total / basic.len().max(1) as f64
+    }
+}
+
+/// The Jensen-Shannon divergence between two weighted distributions, between 0 and 1.
+fn jensen_shannon(a: &[(String, i32)], b: &[(String, i32)]) -> f64 {
+    let normalize = |weights: &[(String, i32)]| -> HashMap<String, f64> {
+        let total: i32 = weights.iter().map(|w| w.1.max(0)).sum();
+        weights
+            .iter()
+            .filter(|w| w.1 > 0)
+            .map(|(k, w)| (k.clone(), f64::from(*w) / f64::from(total)))
+            .collect()
+    };
+    let (p, q) = (normalize(a), normalize(b));
+    if p.is_empty() || q.is_empty() {
+        return if p.is_empty() && q.is_empty() {
+            0.0
+        } else {
+            1.0
+        };
+    }
+
+    let keys: HashSet<&String> = p.keys().chain(q.keys()).collect();
+    let mut divergence = 0.0;
+    for key in keys {
+        let (pk, qk) = (
+            p.get(key).copied().unwrap_or(0.0),
+            q.get(key).copied().unwrap_or(0.0),
+        );
+        let m = (pk + qk) / 2.0;
+        if pk > 0.0 {
+            divergence += pk * (pk / m).log2() / 2.0;
+        }
+        if qk > 0.0 {
+            divergence += qk * (qk / m).log2() / 2.0;
+        }
+    }
+
+    divergence.clamp(0.0, 1.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn distances() {
+        let lang = SynthLang::new(40);
+        let daughter = lang.daughter(1);
+        let stranger = SynthLang::new(41);
+
+        assert!(lang.distance(&lang).abs() < 1e-9);
+        assert!((lang.distance(&stranger) - stranger.distance(&lang)).abs() < 1e-9);
+        assert!(lang.distance(&daughter) < lang.distance(&stranger));
+        assert!(lang.distance(&stranger) <= 1.0);
+    }
+
+    #[test]
+    fn divergence() {
+        let weights = |w: &[i32]| -> Vec<(String, i32)> {
+            w.iter()
+                .enumerate()
+                .map(|(i, w)| (i.to_string(), *w))
+                .collect()
+        };
+
+        assert!(jensen_shannon(&weights(&[1, 2]), &weights(&[2, 4])).abs() < 1e-9);
+        assert!((jensen_shannon(&weights(&[1, 0]), &weights(&[0, 1])) - 1.0).abs() < 1e-9);
+    }
+}
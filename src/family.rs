@@ -0,0 +1,337 @@
+//! Daughter languages: copies of a parent language that have drifted through their own sound
+//! changes, so that words handed down from the parent come out as recognizable cognates.
+
+use crate::phoneme::{self, Features};
+use crate::sound_change::{SoundChange, SoundChanges};
+use crate::{SyllablePartType, SynthLang, Word};
+use rand::prelude::*;
+use rand_pcg::Pcg64;
+use std::collections::HashMap;
+use std::sync::OnceLock;
+
+// (phoneme, the parent phoneme it descends from)
+type Lineage = Vec<(String, String)>;
+
+impl SynthLang {
+    /// Derives a daughter language from this one. The daughter inherits this language's
+    /// inventory and transition weights, then applies its own seeded sound changes (which may
+    /// merge or split phonemes) and lets its syllable and compounding weights drift a little.
+    #[must_use]
+    pub fn daughter(&self, seed: u64) -> SynthLang {
+        let mut rng = Pcg64::seed_from_u64(seed);
+
+        let mut candidates =
+            Self::candidate_sound_changes(&mut rng, &self.vowels, &self.consonants);
+        candidates.shuffle(&mut rng);
+        let count = rng.gen_range(2, 6);
+
+        let mut vowels: Lineage = self.vowels.iter().map(|v| (v.clone(), v.clone())).collect();
+        let mut consonants: Lineage = self
+            .consonants
+            .iter()
+            .map(|c| (c.clone(), c.clone()))
+            .collect();
+
+        let mut sound_changes = SoundChanges::new();
+        for rule in &candidates {
+            if sound_changes.len() == count {
+                break;
+            }
+
+            let (new_vowels, new_consonants) = Self::change_inventory(rule, &vowels, &consonants);
+
+            // Skip rules that do nothing, or that would leave us without vowels or consonants
+            if new_vowels.is_empty()
+                || new_consonants.is_empty()
+                || (new_vowels == vowels && new_consonants == consonants)
+            {
+                continue;
+            }
+
+            vowels = new_vowels;
+            consonants = new_consonants;
+            sound_changes.push(rule.clone());
+        }
+
+        let mut next_part_weights = HashMap::new();
+        for (part, origin) in vowels
+            .iter()
+            .chain(consonants.iter())
+            .chain([("\0".to_string(), "\0".to_string())].iter())
+        {
+            let row = |lineage: &Lineage| -> Vec<(String, i32)> {
+                let mut weights: Vec<(String, i32)> = lineage
+                    .iter()
+                    .map(|(next, next_origin)| (next.clone(), self.weight(origin, next_origin)))
+                    .collect();
+
+                if weights.iter().all(|w| w.1 == 0) {
+                    for w in &mut weights {
+                        w.1 = 1;
+                    }
+                }
+
+                weights
+            };
+
+            let weights = (row(&vowels), row(&consonants));
+            next_part_weights.insert(part.clone(), weights);
+        }
+
+        let (cv_weight, vc_weight, cvc_weight) =
+            Self::drift(&mut rng, (self.cv_weight, self.vc_weight, self.cvc_weight));
+        let compound_weights = Self::drift(&mut rng, self.compound_weights);
+
+        Self {
+            consonants: consonants.into_iter().map(|c| c.0).collect(),
+            vowels: vowels.into_iter().map(|v| v.0).collect(),
+            cv_weight,
+            vc_weight,
+            cvc_weight,
+            next_part_weights,
+            compound_weights,
+            parent: Some(Box::new(self.clone())),
+            sound_changes,
+            seed,
+            rng,
+            uniqueness: None,
+            content_filter: self.content_filter.clone(),
+            min_pronounceability: self.min_pronounceability,
+            sonority: self.sonority,
+            casing: OnceLock::new(),
+        }
+    }
+
+    /// The language this one descends from, if it was created with `daughter`.
+    #[must_use]
+    pub fn parent(&self) -> Option<&SynthLang> {
+        self.parent.as_deref()
+    }
+
+    /// The sound changes that separate this language from its parent.
+    #[must_use]
+    pub fn sound_changes(&self) -> &SoundChanges {
+        &self.sound_changes
+    }
+
+    /// Hands a word from the parent language down into this one.
+    #[must_use]
+    pub fn cognate(&self, word: &Word) -> Word {
+        self.evolve(word, &self.sound_changes)
+    }
+
+    fn drift(rng: &mut Pcg64, weights: (i32, i32, i32)) -> (i32, i32, i32) {
+        let mut drifted = [weights.0, weights.1, weights.2];
+
+        if rng.gen_bool(0.5) {
+            let i = rng.gen_range(0, 3);
+            let step = if rng.gen_bool(0.5) { 1 } else { -1 };
+            drifted[i] = (drifted[i] + step).clamp(0, 4);
+        }
+
+        if drifted.iter().all(|w| *w == 0) {
+            weights
+        } else {
+            (drifted[0], drifted[1], drifted[2])
+        }
+    }
+
+    /// Works out what the inventory looks like after `rule`. Unconditional changes replace
+    /// phonemes (mergers), conditional ones add the result alongside the original (splits).
+    fn change_inventory(
+        rule: &SoundChange,
+        vowels: &Lineage,
+        consonants: &Lineage,
+    ) -> (Lineage, Lineage) {
+        let inventory: Vec<String> = vowels
+            .iter()
+            .chain(consonants.iter())
+            .map(|p| p.0.clone())
+            .collect();
+
+        let mut new_vowels: Lineage = vec![];
+        let mut new_consonants: Lineage = vec![];
+
+        let mut add = |phoneme: String, origin: String, part_type: SyllablePartType| {
+            let is_vowel = match phoneme::features(&phoneme) {
+                Some(f) => f.is_vowel(),
+                None => matches!(part_type, SyllablePartType::Vowel),
+            };
+            let lineage = if is_vowel {
+                &mut new_vowels
+            } else {
+                &mut new_consonants
+            };
+
+            if !lineage.iter().any(|p| p.0 == phoneme) {
+                lineage.push((phoneme, origin));
+            }
+        };
+
+        let parts = vowels
+            .iter()
+            .map(|p| (p, SyllablePartType::Vowel))
+            .chain(consonants.iter().map(|p| (p, SyllablePartType::Consonant)));
+
+        for ((phoneme, origin), part_type) in parts {
+            match rule.outcome(phoneme, part_type, &inventory) {
+                None => add(phoneme.clone(), origin.clone(), part_type),
+                Some(outcome) => {
+                    if rule.is_conditional() {
+                        add(phoneme.clone(), origin.clone(), part_type);
+                    }
+                    if let Some(changed) = outcome {
+                        add(changed, origin.clone(), part_type);
+                    }
+                }
+            }
+        }
+
+        (new_vowels, new_consonants)
+    }
+
+    fn candidate_sound_changes(
+        rng: &mut Pcg64,
+        vowels: &[String],
+        consonants: &[String],
+    ) -> Vec<SoundChange> {
+        let has = |p: &str| vowels.iter().chain(consonants.iter()).any(|v| v == p);
+
+        let mut rules: Vec<String> = vec![
+            // Lenition
+            "[voiceless stop] > [voiced] / V_V".to_string(),
+            "[voiced stop] > [fricative] / V_V".to_string(),
+            // Final devoicing
+            "[voiced obstruent] > [-voiced] / _#".to_string(),
+            // Nasals merge at the end of words
+            "[nasal] > n / _#".to_string(),
+            // Umlaut
+            "[back] > [front] / _C i".to_string(),
+            // Raising before nasals
+            "[mid] > [high] / _[nasal]".to_string(),
+        ];
+
+        // Palatalization
+        for (from, to) in &[
+            ("k", "ch"),
+            ("c", "ch"),
+            ("g", "j"),
+            ("t", "ts"),
+            ("s", "sh"),
+        ] {
+            if has(from) {
+                rules.push(format!("{} > {} / _[high front]", from, to));
+            }
+        }
+
+        if has("h") {
+            rules.push("h > ∅".to_string());
+        }
+        if has("s") {
+            rules.push("s > h / #_".to_string());
+        }
+
+        // Diphthongs flatten into their first vowel
+        for v in vowels {
+            if phoneme::features(v).is_some_and(|f| f.has(phoneme::Feature::Diphthong)) {
+                if let Some(first) = v.chars().next() {
+                    rules.push(format!("{} > {}", v, first));
+                }
+            }
+        }
+
+        // Mergers between phonemes that only differ in a single feature
+        let mut mergers = vec![];
+        for a in vowels.iter().chain(consonants.iter()) {
+            for b in vowels.iter().chain(consonants.iter()) {
+                match (phoneme::features(a), phoneme::features(b)) {
+                    (Some(fa), Some(fb)) if a != b && Self::nearly_equal(fa, fb) => {
+                        mergers.push(format!("{} > {}", a, b));
+                    }
+                    _ => {}
+                }
+            }
+        }
+        rules.extend(mergers.choose_multiple(rng, 3).cloned());
+
+        rules.iter().filter_map(|r| r.parse().ok()).collect()
+    }
+
+    fn nearly_equal(a: Features, b: Features) -> bool {
+        match (a, b) {
+            (
+                Features::Consonant {
+                    place: pa,
+                    manner: ma,
+                    voiced: va,
+                },
+                Features::Consonant {
+                    place: pb,
+                    manner: mb,
+                    voiced: vb,
+                },
+            ) => {
+                [pa == pb, ma == mb, va == vb]
+                    .iter()
+                    .filter(|same| **same)
+                    .count()
+                    >= 2
+            }
+            (
+                Features::Vowel {
+                    height: ha,
+                    backness: ba,
+                    rounded: ra,
+                    diphthong: false,
+                },
+                Features::Vowel {
+                    height: hb,
+                    backness: bb,
+                    rounded: rb,
+                    diphthong: false,
+                },
+            ) => {
+                [ha == hb, ba == bb, ra == rb]
+                    .iter()
+                    .filter(|same| **same)
+                    .count()
+                    >= 2
+            }
+            (_, _) => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn daughters() {
+        for seed in 0..50 {
+            let mut parent = SynthLang::new(seed);
+            let mut daughter = parent.daughter(seed + 1000);
+
+            assert!(!daughter.sound_changes().is_empty());
+            assert!(daughter.parent().is_some());
+
+            for _ in 0..20 {
+                daughter.word();
+
+                let word = parent.word();
+                let cognate = daughter.cognate(&word);
+                for syllable in &cognate.parts {
+                    for part in &syllable.parts {
+                        assert!(
+                            daughter.features(&part.value).is_some()
+                                || phoneme::features(&part.value).is_none(),
+                            "{} not in daughter inventory {:?}",
+                            part.value,
+                            daughter.sound_changes().to_string()
+                        );
+                    }
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,307 @@
+//! Content filtering: keeping offensive words and real-world names out of generated output.
+
+use crate::{SynthLang, Word};
+use std::fmt;
+use std::sync::Arc;
+
+// Matched anywhere inside a word
+const BLOCKED_SUBSTRINGS: &[&str] = &[
+    "fuck", "shit", "cunt", "cock", "dick", "piss", "twat", "wank", "slut", "whore", "bitch",
+    "bastard", "nigg", "nigr", "fagg", "rape", "nazi", "hitler", "kike", "spic", "chink", "retard",
+    "tranny", "dyke", "penis", "vagina", "anus", "pussy", "boob", "tits", "porn", "jizz", "dildo",
+    "puta", "puto", "merde", "scheiss", "kurwa", "cazzo", "mierda", "cono", "pendejo", "blyat",
+    "disney", "google", "pepsi", "nintendo", "adidas",
+];
+
+// Only matched as the whole word, since they turn up inside plenty of innocent ones
+const BLOCKED_WORDS: &[&str] = &[
+    "ass", "arse", "tit", "cum", "fag", "poo", "pee", "sex", "jew", "kkk", "nig", "homo", "gay",
+    "cul", "con", "pute", "nike", "sony",
+];
+
+/// Decides whether a generated word may be shown.
+pub trait ContentFilter: fmt::Debug + Send + Sync {
+    fn is_blocked(&self, word: &str) -> bool;
+}
+
+/// A list of blocked words and substrings.
+///
+/// With phonetic matching on (the default), words are compared by a rough sound skeleton, so
+/// that "phuk" and "fück" are caught along with "fuck".
+#[derive(Debug, Clone)]
+pub struct Blocklist {
+    substrings: Vec<String>,
+    words: Vec<String>,
+    phonetic: bool,
+}
+
+impl Default for Blocklist {
+    /// The bundled list of English and other-language profanity, slurs and trademarks.
+    fn default() -> Self {
+        let mut blocklist = Self::empty();
+        for substring in BLOCKED_SUBSTRINGS {
+            blocklist.add_substring(substring);
+        }
+        for word in BLOCKED_WORDS {
+            blocklist.add_word(word);
+        }
+
+        blocklist
+    }
+}
+
+impl Blocklist {
+    /// A list that doesn't block anything yet.
+    #[must_use]
+    pub fn empty() -> Self {
+        Self {
+            substrings: vec![],
+            words: vec![],
+            phonetic: true,
+        }
+    }
+
+    /// Blocks every word that contains `substring`.
+    pub fn add_substring(&mut self, substring: &str) {
+        self.substrings.push(substring.to_lowercase());
+    }
+
+    /// Blocks `word` itself.
+    pub fn add_word(&mut self, word: &str) {
+        self.words.push(word.to_lowercase());
+    }
+
+    pub fn set_phonetic(&mut self, phonetic: bool) {
+        self.phonetic = phonetic;
+    }
+
+    fn normalize(&self, text: &str) -> String {
+        let text = text.to_lowercase();
+        if self.phonetic {
+            skeleton(&text)
+        } else {
+            text
+        }
+    }
+}
+
+impl ContentFilter for Blocklist {
+    fn is_blocked(&self, word: &str) -> bool {
+        let word = self.normalize(word);
+
+        self.words.iter().any(|w| self.normalize(w) == word)
+            || self
+                .substrings
+                .iter()
+                .any(|s| word.contains(&self.normalize(s)))
+    }
+}
+
+/// Reduces a word to how it roughly sounds: diacritics are dropped, letters that spell the
+/// same sound are merged and doubled letters are collapsed.
+fn skeleton(text: &str) -> String {
+    let folded: String = text.chars().filter_map(fold).collect();
+
+    let mut merged = folded;
+    for (from, to) in &[
+        ("ph", "f"),
+        ("ck", "k"),
+        ("qu", "kw"),
+        ("c", "k"),
+        ("q", "k"),
+        ("x", "ks"),
+        ("y", "i"),
+        ("w", "u"),
+        ("oo", "u"),
+        ("ee", "i"),
+    ] {
+        merged = merged.replace(from, to);
+    }
+
+    let mut skeleton = String::new();
+    for c in merged.chars() {
+        if !skeleton.ends_with(c) {
+            skeleton.push(c);
+        }
+    }
+
+    skeleton
+}
+
+/// Folds a letter with diacritics into its base letter, and drops anything that isn't a
+/// letter.
+fn fold(c: char) -> Option<char> {
+    const FOLDS: &[(&str, char)] = &[
+        ("àáâãäåāă", 'a'),
+        ("èéêëēĕėě", 'e'),
+        ("ìíîïĩīĭ", 'i'),
+        ("òóôõöōŏőø", 'o'),
+        ("ùúûüũūŭůű", 'u'),
+        ("ñń", 'n'),
+        ("çćĉ", 'c'),
+        ("šś", 's'),
+        ("źż", 'z'),
+        ("ðđ", 'd'),
+        ("ġ", 'g'),
+        ("ł", 'l'),
+        ("ŕ", 'r'),
+        ("ĥ", 'h'),
+        ("ĵй", 'j'),
+        ("ẅŵ", 'w'),
+        ("и", 'i'),
+    ];
+
+    if c.is_ascii_alphabetic() {
+        return Some(c);
+    }
+
+    FOLDS
+        .iter()
+        .find(|(from, _)| from.contains(c))
+        .map(|(_, to)| *to)
+        .or_else(|| Some(c).filter(|c| c.is_alphabetic()))
+}
+
+impl SynthLang {
+    /// Sets the filter that every generated word is checked against, from `word()` and
+    /// `word_for()` to affixes, names and translations, or turns filtering off with `None`.
+    /// Blocked words are regenerated, and keyed words get a salted key, so output stays
+    /// deterministic.
+    /// Unlike uniqueness and pronounceability, the filter is never given up on, so it has to let
+    /// some of the language's words through.
+    ///
+    /// No filter is set by default; `Blocklist::default()` is a reasonable one to start with.
+    pub fn set_content_filter(&mut self, filter: Option<Arc<dyn ContentFilter>>) {
+        self.content_filter = filter;
+        self.clear_casing();
+    }
+
+    #[must_use]
+    pub fn content_filter(&self) -> Option<&dyn ContentFilter> {
+        self.content_filter.as_deref()
+    }
+
+    /// Whether this language's content filter blocks `word`.
+    #[must_use]
+    pub fn is_blocked(&self, word: &Word) -> bool {
+        self.content_filter
+            .as_ref()
+            .is_some_and(|f| f.is_blocked(&word.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::vocabulary;
+
+    #[test]
+    fn blocklist() {
+        let blocklist = Blocklist::default();
+        assert!(blocklist.is_blocked("fuck"));
+        assert!(blocklist.is_blocked("Phuk"));
+        assert!(blocklist.is_blocked("fück"));
+        assert!(blocklist.is_blocked("ashitak"));
+        assert!(blocklist.is_blocked("ass"));
+        assert!(!blocklist.is_blocked("classic"));
+        assert!(!blocklist.is_blocked("tarvos"));
+
+        let mut exact = Blocklist::empty();
+        exact.set_phonetic(false);
+        exact.add_substring("fuck");
+        assert!(!exact.is_blocked("phuk"));
+    }
+
+    #[test]
+    fn regenerates_blocked_words() {
+        let words = |filter: Option<Arc<dyn ContentFilter>>| {
+            let mut lang = SynthLang::new(10);
+            lang.set_content_filter(filter);
+            (0..50).map(|_| lang.word().to_string()).collect::<Vec<_>>()
+        };
+
+        let unfiltered = words(None);
+
+        // Block a few of the words we know will come up
+        let mut blocklist = Blocklist::empty();
+        for word in unfiltered.iter().skip(5).step_by(10) {
+            blocklist.add_word(word);
+        }
+        let blocklist = Arc::new(blocklist);
+
+        let filtered = words(Some(blocklist.clone()));
+        assert_eq!(filtered, words(Some(blocklist.clone())));
+        assert!(filtered.iter().all(|w| !blocklist.is_blocked(w)));
+        assert_eq!(filtered[..5], unfiltered[..5]);
+        assert_ne!(filtered[5], unfiltered[5]);
+    }
+
+    #[test]
+    fn keyed_words() {
+        let mut lang = SynthLang::new(14);
+        let mountain = lang.word_for("mountain").to_string();
+        let river = lang.word_for_concept(vocabulary::concept("river").unwrap());
+        let plural = lang.grammar().plural.form.to_string();
+        let diminutive = lang.naming_conventions().diminutive.form.to_string();
+
+        let mut blocklist = Blocklist::empty();
+        blocklist.set_phonetic(false);
+        blocklist.add_word(&mountain);
+        blocklist.add_word(&river.to_string());
+        blocklist.add_word(&plural);
+        blocklist.add_word(&diminutive);
+        let blocklist = Arc::new(blocklist);
+        lang.set_content_filter(Some(blocklist.clone()));
+
+        assert_ne!(lang.word_for("mountain").to_string(), mountain);
+        assert_eq!(
+            lang.word_for("mountain").to_string(),
+            lang.word_for("mountain").to_string()
+        );
+        assert_ne!(lang.grammar().plural.form.to_string(), plural);
+        assert_ne!(
+            lang.naming_conventions().diminutive.form.to_string(),
+            diminutive
+        );
+        for (_, word) in lang.dictionary().iter() {
+            assert!(!blocklist.is_blocked(&word.to_string()));
+        }
+
+        // Inflected words that end up blocked get a linking syllable
+        let grammar = lang.grammar();
+        let wolf = lang.word_for_concept(vocabulary::concept("wolf").unwrap());
+        let wolves = grammar.plural.apply(&wolf).to_string();
+        let mut blocklist = Blocklist::empty();
+        blocklist.set_phonetic(false);
+        blocklist.add_word(&wolves);
+        lang.set_content_filter(Some(Arc::new(blocklist)));
+
+        let translation = lang.translate_text("wolves");
+        assert_ne!(translation.text.to_lowercase(), wolves);
+        assert_eq!(translation, lang.translate_text("wolves"));
+    }
+
+    #[test]
+    fn never_blocked() {
+        let mut lang = SynthLang::new(10);
+        let left = lang.word();
+        let right = lang.word();
+
+        // Every way of joining these two words is blocked
+        let mut blocklist = Blocklist::empty();
+        blocklist.set_phonetic(false);
+        blocklist.add_substring(&left.to_string());
+        blocklist.add_substring(&right.to_string());
+        // And so are most vowels
+        for vowel in lang.vowels.iter().skip(1) {
+            blocklist.add_substring(vowel);
+        }
+        let blocklist = Arc::new(blocklist);
+        lang.set_content_filter(Some(blocklist.clone()));
+
+        for _ in 0..20 {
+            assert!(!blocklist.is_blocked(&lang.word().to_string()));
+            assert!(!blocklist.is_blocked(&lang.compound(&left, &right).to_string()));
+        }
+    }
+}
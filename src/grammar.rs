@@ -0,0 +1,435 @@
+//! A tiny per-language syntax: word order, modifier placement, adpositions, question particles
+//! and a handful of affixes, plus rendering of simple structured sentences.
+
+use crate::{Lexicon, SynthLang, Word};
+use rand::prelude::*;
+use rand_pcg::Pcg64;
+
+// How many linking syllables are tried when an inflected word is blocked
+const LINK_ATTEMPTS: usize = 20;
+
+#[allow(clippy::upper_case_acronyms)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WordOrder {
+    SOV,
+    SVO,
+    VSO,
+    VOS,
+    OVS,
+    OSV,
+}
+
+/// Where something goes relative to the word it belongs to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Position {
+    Before,
+    After,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Tense {
+    Present,
+    Past,
+    Future,
+}
+
+#[derive(Debug, Clone)]
+pub struct Affix {
+    pub form: Word,
+    pub position: Position,
+}
+
+#[derive(Debug, Clone)]
+pub struct Grammar {
+    pub word_order: WordOrder,
+    pub adjective: Position,
+    pub genitive: Position,
+    /// `Before` for prepositions, `After` for postpositions.
+    pub adposition: Position,
+    pub question_particle: Word,
+    /// `Before` puts the particle at the start of a question, `After` at the end.
+    pub question: Position,
+    pub plural: Affix,
+    pub possessive: Affix,
+    pub past: Affix,
+    pub future: Affix,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NounPhrase {
+    pub noun: String,
+    pub adjectives: Vec<String>,
+    pub possessor: Option<Box<NounPhrase>>,
+    pub plural: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Modifier {
+    pub adposition: String,
+    pub object: NounPhrase,
+}
+
+/// A sentence to translate. Words are meaning keys for a `Lexicon`, e.g. English lemmas.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Sentence {
+    pub subject: NounPhrase,
+    pub verb: String,
+    pub tense: Tense,
+    pub object: Option<NounPhrase>,
+    pub modifiers: Vec<Modifier>,
+    pub question: bool,
+}
+
+impl NounPhrase {
+    #[must_use]
+    pub fn new(noun: &str) -> Self {
+        Self {
+            noun: noun.to_string(),
+            adjectives: vec![],
+            possessor: None,
+            plural: false,
+        }
+    }
+
+    #[must_use]
+    pub fn adjective(mut self, adjective: &str) -> Self {
+        self.adjectives.push(adjective.to_string());
+        self
+    }
+
+    #[must_use]
+    pub fn possessor(mut self, possessor: NounPhrase) -> Self {
+        self.possessor = Some(Box::new(possessor));
+        self
+    }
+
+    #[must_use]
+    pub fn plural(mut self) -> Self {
+        self.plural = true;
+        self
+    }
+}
+
+impl Sentence {
+    #[must_use]
+    pub fn new(subject: NounPhrase, verb: &str) -> Self {
+        Self {
+            subject,
+            verb: verb.to_string(),
+            tense: Tense::Present,
+            object: None,
+            modifiers: vec![],
+            question: false,
+        }
+    }
+
+    #[must_use]
+    pub fn object(mut self, object: NounPhrase) -> Self {
+        self.object = Some(object);
+        self
+    }
+
+    #[must_use]
+    pub fn tense(mut self, tense: Tense) -> Self {
+        self.tense = tense;
+        self
+    }
+
+    #[must_use]
+    pub fn modifier(mut self, adposition: &str, object: NounPhrase) -> Self {
+        self.modifiers.push(Modifier {
+            adposition: adposition.to_string(),
+            object,
+        });
+        self
+    }
+
+    #[must_use]
+    pub fn question(mut self) -> Self {
+        self.question = true;
+        self
+    }
+}
+
+impl Affix {
+    /// Attaches this affix to `word`.
+    #[must_use]
+    pub fn apply(&self, word: &Word) -> Word {
+        let mut parts = word.parts.clone();
+        match self.position {
+            Position::Before => {
+                parts.splice(0..0, self.form.parts.iter().cloned());
+            }
+            Position::After => parts.extend(self.form.parts.iter().cloned()),
+        }
+
+        Word::new(parts, word.compound_rule)
+    }
+}
+
+impl Grammar {
+    fn random(lang: &SynthLang, rng: &mut Pcg64) -> Self {
+        // Roughly how common each order is among the world's languages
+        let orders = [
+            (WordOrder::SOV, 45),
+            (WordOrder::SVO, 42),
+            (WordOrder::VSO, 9),
+            (WordOrder::VOS, 3),
+            (WordOrder::OVS, 1),
+            (WordOrder::OSV, 1),
+        ];
+        let word_order = orders.choose_weighted(rng, |o| o.1).unwrap().0;
+
+        // Verb-final languages overwhelmingly use postpositions and put possessors first
+        let verb_final = matches!(word_order, WordOrder::SOV | WordOrder::OSV);
+        let harmonic = |rng: &mut Pcg64| {
+            if rng.gen_bool(0.85) == verb_final {
+                Position::Before
+            } else {
+                Position::After
+            }
+        };
+        let genitive = harmonic(rng);
+        let adposition = if harmonic(rng) == Position::Before {
+            Position::After
+        } else {
+            Position::Before
+        };
+
+        let either = |rng: &mut Pcg64, p: f64| {
+            if rng.gen_bool(p) {
+                Position::Before
+            } else {
+                Position::After
+            }
+        };
+        let adjective = either(rng, 0.4);
+        let question = either(rng, 0.3);
+
+        let affix = |rng: &mut Pcg64| Affix {
+            form: lang.affix_form(rng, None),
+            // Suffixes are much more common than prefixes
+            position: either(rng, 0.25),
+        };
+
+        Self {
+            word_order,
+            adjective,
+            genitive,
+            adposition,
+            question_particle: lang.affix_form(rng, None),
+            question,
+            plural: affix(rng),
+            possessive: affix(rng),
+            past: affix(rng),
+            future: affix(rng),
+        }
+    }
+
+    fn cognate(&self, lang: &SynthLang) -> Self {
+        let mut rng = lang.keyed_rng(SynthLang::hash_key("\0grammar"));
+        let mut affix = |a: &Affix| Affix {
+            form: lang.affix_form(&mut rng, Some(lang.cognate(&a.form))),
+            position: a.position,
+        };
+
+        Self {
+            plural: affix(&self.plural),
+            possessive: affix(&self.possessive),
+            past: affix(&self.past),
+            future: affix(&self.future),
+            question_particle: lang
+                .affix_form(&mut rng, Some(lang.cognate(&self.question_particle))),
+            ..self.clone()
+        }
+    }
+}
+
+impl SynthLang {
+    /// A one-syllable form for an affix or particle: `inherited` (such as a cognate) if the
+    /// content filter lets it through, and otherwise a new syllable from `rng`.
+    pub(crate) fn affix_form(&self, rng: &mut Pcg64, inherited: Option<Word>) -> Word {
+        self.generate_checked(None, 0, |n| match (&inherited, n) {
+            (Some(word), 0) => word.clone(),
+            _ => self.generate_word_with_lengths(rng, &[(1, 1)]),
+        })
+    }
+
+    /// Attaches `affix` to `word`. If the content filter blocks the result, a linking syllable
+    /// goes between them, always the same one for the same word and affix. If no syllable
+    /// helps, the word is replaced altogether.
+    pub(crate) fn inflect(&self, affix: &Affix, word: &Word) -> Word {
+        let key = format!("\0inflect:{}:{}", word, affix.form);
+        let mut rng = self.keyed_rng(Self::hash_key(&key));
+
+        self.generate_checked(None, 0, |n| match n {
+            0 => affix.apply(word),
+            _ if n <= LINK_ATTEMPTS => {
+                let link = Affix {
+                    form: Word::new(vec![self.syllable(&mut rng, None)], word.compound_rule),
+                    position: affix.position,
+                };
+                affix.apply(&link.apply(word))
+            }
+            _ => self.generate_word(&mut rng),
+        })
+    }
+
+    /// This language's grammar, derived from its seed. Daughter languages keep their parent's
+    /// grammar, with cognate affixes.
+    #[must_use]
+    pub fn grammar(&self) -> Grammar {
+        match &self.parent {
+            Some(parent) => parent.grammar().cognate(self),
+            None => Grammar::random(self, &mut self.keyed_rng(Self::hash_key("\0grammar"))),
+        }
+    }
+
+    /// Renders a structured sentence in this language, taking words from (and adding any
+    /// missing words to) `lexicon`.
+    pub fn translate(&self, lexicon: &mut Lexicon, sentence: &Sentence) -> String {
+        let grammar = self.grammar();
+        let mut word = |key: &str| lexicon.word(self, key).clone();
+
+        let verb = word(&sentence.verb);
+        let verb = match sentence.tense {
+            Tense::Present => verb,
+            Tense::Past => self.inflect(&grammar.past, &verb),
+            Tense::Future => self.inflect(&grammar.future, &verb),
+        };
+        let verb = vec![verb];
+
+        let subject = self.noun_phrase(&grammar, &mut word, &sentence.subject, false);
+        let object = match &sentence.object {
+            Some(object) => self.noun_phrase(&grammar, &mut word, object, false),
+            None => vec![],
+        };
+
+        let mut modifiers = vec![];
+        for modifier in &sentence.modifiers {
+            let adposition = vec![word(&modifier.adposition)];
+            let object = self.noun_phrase(&grammar, &mut word, &modifier.object, false);
+            modifiers.extend(place(grammar.adposition, adposition, object));
+        }
+
+        let (s, v, o) = (subject, verb, object);
+        let mut words = match grammar.word_order {
+            WordOrder::SOV => [s, o, modifiers, v].concat(),
+            WordOrder::OSV => [o, s, modifiers, v].concat(),
+            WordOrder::SVO => [s, v, o, modifiers].concat(),
+            WordOrder::VSO => [v, s, o, modifiers].concat(),
+            WordOrder::VOS => [v, o, s, modifiers].concat(),
+            WordOrder::OVS => [o, v, s, modifiers].concat(),
+        };
+
+        if sentence.question {
+            let particle = vec![grammar.question_particle.clone()];
+            words = place(grammar.question, particle, words);
+        }
+
+        let words: Vec<String> = words.iter().map(ToString::to_string).collect();
+        let mut text = self.cached_casing().capitalize(&words.join(" "));
+        text.push(if sentence.question { '?' } else { '.' });
+
+        text
+    }
+
+    fn noun_phrase<F>(
+        &self,
+        grammar: &Grammar,
+        word: &mut F,
+        phrase: &NounPhrase,
+        possessive: bool,
+    ) -> Vec<Word>
+    where
+        F: FnMut(&str) -> Word,
+    {
+        let mut noun = word(&phrase.noun);
+        if phrase.plural {
+            noun = self.inflect(&grammar.plural, &noun);
+        }
+        if possessive {
+            noun = self.inflect(&grammar.possessive, &noun);
+        }
+
+        let mut words = vec![noun];
+        for adjective in &phrase.adjectives {
+            words = place(grammar.adjective, vec![word(adjective)], words);
+        }
+
+        if let Some(possessor) = &phrase.possessor {
+            let possessor = self.noun_phrase(grammar, word, possessor, true);
+            words = place(grammar.genitive, possessor, words);
+        }
+
+        words
+    }
+}
+
+fn place<T: Clone>(position: Position, dependent: Vec<T>, head: Vec<T>) -> Vec<T> {
+    match position {
+        Position::Before => [dependent, head].concat(),
+        Position::After => [head, dependent].concat(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn word_order() {
+        for seed in 0..20 {
+            let lang = SynthLang::new(seed);
+            let grammar = lang.grammar();
+            let mut lexicon = Lexicon::new();
+
+            let sentence =
+                Sentence::new(NounPhrase::new("king"), "see").object(NounPhrase::new("dragon"));
+            let translated = lang.translate(&mut lexicon, &sentence).to_lowercase();
+            let position = |key: &str| {
+                let word = lexicon.get(key).unwrap().to_string();
+                translated
+                    .trim_end_matches('.')
+                    .split(' ')
+                    .position(|w| w == word)
+                    .unwrap()
+            };
+
+            let (s, v, o) = (position("king"), position("see"), position("dragon"));
+            let expected = match grammar.word_order {
+                WordOrder::SOV => s < o && o < v,
+                WordOrder::SVO => s < v && v < o,
+                WordOrder::VSO => v < s && s < o,
+                WordOrder::VOS => v < o && o < s,
+                WordOrder::OVS => o < v && v < s,
+                WordOrder::OSV => o < s && s < v,
+            };
+            assert!(expected, "{:?}: {}", grammar.word_order, translated);
+        }
+    }
+
+    #[test]
+    fn questions_and_affixes() {
+        let lang = SynthLang::new(4);
+        let grammar = lang.grammar();
+        let mut lexicon = Lexicon::new();
+
+        let sentence = Sentence::new(NounPhrase::new("wolf").plural(), "hunt")
+            .tense(Tense::Past)
+            .modifier("in", NounPhrase::new("forest").adjective("dark"))
+            .question();
+        let translated = lang.translate(&mut lexicon, &sentence);
+
+        assert!(translated.ends_with('?'));
+        assert!(translated
+            .to_lowercase()
+            .contains(&grammar.question_particle.to_string()));
+
+        let wolves = grammar
+            .plural
+            .apply(lexicon.get("wolf").unwrap())
+            .to_string();
+        assert!(translated.to_lowercase().contains(&wolves));
+    }
+}
@@ -0,0 +1,155 @@
+//! Changing a language's phonemes and transition weights after it has been generated.
+
+use crate::{SyllablePartType, SynthLang};
+
+impl SynthLang {
+    /// Adds `phoneme` to the inventory. Its weights are the ones a generated language gives
+    /// to the last phoneme of its kind, both for what follows it and for it following anything
+    /// else. Returns false if the language already has it.
+    pub fn add_phoneme(&mut self, phoneme: &str, part_type: SyllablePartType) -> bool {
+        if self.has_phoneme(phoneme) || phoneme == "\0" || phoneme.is_empty() {
+            return false;
+        }
+
+        let list = match part_type {
+            SyllablePartType::Vowel => &mut self.vowels,
+            SyllablePartType::Consonant => &mut self.consonants,
+        };
+        list.push(phoneme.to_string());
+        let weight = Self::next_part_weight(list.len() - 1, list.len());
+
+        for (vowels, consonants) in self.next_part_weights.values_mut() {
+            match part_type {
+                SyllablePartType::Vowel => vowels.push((phoneme.to_string(), weight)),
+                SyllablePartType::Consonant => consonants.push((phoneme.to_string(), weight)),
+            }
+        }
+
+        let row = |list: &[String]| -> Vec<(String, i32)> {
+            list.iter()
+                .enumerate()
+                .map(|(i, p)| (p.clone(), Self::next_part_weight(i, list.len())))
+                .collect()
+        };
+        let weights = (row(&self.vowels), row(&self.consonants));
+        self.next_part_weights.insert(phoneme.to_string(), weights);
+        self.clear_casing();
+
+        true
+    }
+
+    /// Removes `phoneme` from the inventory. Anything that could only be followed by it can
+    /// then be followed by any phoneme of its kind. Returns false if the language doesn't have
+    /// it, or if it's the last vowel or consonant.
+    pub fn remove_phoneme(&mut self, phoneme: &str) -> bool {
+        let list = if self.vowels.iter().any(|v| v == phoneme) {
+            &mut self.vowels
+        } else if self.consonants.iter().any(|c| c == phoneme) {
+            &mut self.consonants
+        } else {
+            return false;
+        };
+        if list.len() == 1 {
+            return false;
+        }
+        list.retain(|p| p != phoneme);
+
+        self.next_part_weights.remove(phoneme);
+        for (vowels, consonants) in self.next_part_weights.values_mut() {
+            for weights in [vowels, consonants] {
+                weights.retain(|w| w.0 != phoneme);
+                if weights.iter().all(|w| w.1 == 0) {
+                    for w in weights.iter_mut() {
+                        w.1 = 1;
+                    }
+                }
+            }
+        }
+        self.clear_casing();
+
+        true
+    }
+
+    /// Sets how likely `next` is to follow `previous` (`"\0"` for the start of a syllable),
+    /// relative to the other phonemes of its kind. Returns false if either phoneme isn't in the
+    /// inventory, or if nothing of `next`'s kind could follow `previous` anymore.
+    pub fn set_transition_weight(&mut self, previous: &str, next: &str, weight: i32) -> bool {
+        let part_type = if self.vowels.iter().any(|v| v == next) {
+            SyllablePartType::Vowel
+        } else if self.consonants.iter().any(|c| c == next) {
+            SyllablePartType::Consonant
+        } else {
+            return false;
+        };
+
+        let weights = match (self.next_part_weights.get_mut(previous), part_type) {
+            (Some((vowels, _)), SyllablePartType::Vowel) => vowels,
+            (Some((_, consonants)), SyllablePartType::Consonant) => consonants,
+            (None, _) => return false,
+        };
+        let others: i32 = weights.iter().filter(|w| w.0 != next).map(|w| w.1).sum();
+        let weight = weight.max(0);
+        if others + weight == 0 {
+            return false;
+        }
+
+        for w in weights.iter_mut().filter(|w| w.0 == next) {
+            w.1 = weight;
+        }
+        self.clear_casing();
+
+        true
+    }
+
+    pub(crate) fn has_phoneme(&self, phoneme: &str) -> bool {
+        self.vowels
+            .iter()
+            .chain(self.consonants.iter())
+            .any(|p| p == phoneme)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn inventory() {
+        let mut lang = SynthLang::new(12);
+        let vowel = lang.vowels[0].clone();
+
+        assert!(lang.add_phoneme("ʘ", SyllablePartType::Consonant));
+        assert!(!lang.add_phoneme("ʘ", SyllablePartType::Consonant));
+        assert!(lang.set_transition_weight("\0", "ʘ", 1000));
+        assert!((0..50).any(|_| lang.word().to_string().contains('ʘ')));
+
+        assert!(lang.remove_phoneme(&vowel));
+        assert!(!lang.remove_phoneme(&vowel));
+        assert!((0..200).all(|_| !lang.word().phonemes().any(|p| p == vowel)));
+
+        while lang.vowels.len() > 1 {
+            let vowel = lang.vowels[0].clone();
+            assert!(lang.remove_phoneme(&vowel));
+        }
+        assert!(!lang.remove_phoneme(&lang.vowels[0].clone()));
+        for _ in 0..50 {
+            lang.word();
+        }
+    }
+
+    #[test]
+    fn transition_weights() {
+        let mut lang = SynthLang::new(13);
+        let only = lang.vowels.last().unwrap().clone();
+
+        for vowel in lang.vowels.clone() {
+            lang.set_transition_weight("\0", &vowel, 0);
+        }
+        assert_eq!(
+            lang.transition_probability("\0", &only, SyllablePartType::Vowel),
+            1.0
+        );
+        assert!(!lang.set_transition_weight("\0", &only, 0));
+        assert!(!lang.set_transition_weight("\0", "ʘ", 1));
+    }
+}
@@ -0,0 +1,379 @@
+//! A persistent mapping from meanings to generated words.
+
+use crate::vocabulary::{self, Concept, CORE_VOCABULARY};
+use crate::{SynthLang, Word};
+use rand::Rng;
+use std::collections::{BTreeMap, BTreeSet};
+use std::io;
+
+// How many other words a meaning tries before it settles for a homophone
+const ATTEMPTS: usize = 100;
+
+/// Assigns each meaning key (`"mountain"`) a word.
+///
+/// Words are derived from the language's seed and the key alone, so a key gets the same word no
+/// matter which order meanings are looked up in. When two meanings would get the same word, the
+/// one that sorts first keeps it and the other gets a different word, even if it was given the
+/// shared word before the first meaning was looked up. A language too small to have another
+/// word for it gives it a homophone after all. A lexicon should only be used with the language
+/// it was created for.
+#[derive(Debug, Clone, Default)]
+pub struct Lexicon {
+    entries: BTreeMap<String, Word>,
+    pinned: BTreeSet<String>,
+    allow_homophones: bool,
+}
+
+impl Lexicon {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// By default, a meaning whose word is already taken by another meaning gets a different
+    /// word. Allowing homophones turns that off.
+    pub fn set_allow_homophones(&mut self, allow: bool) {
+        self.allow_homophones = allow;
+    }
+
+    /// Returns the word for `meaning`, generating it from `lang` the first time.
+    pub fn word(&mut self, lang: &SynthLang, meaning: &str) -> &Word {
+        if !self.entries.contains_key(meaning) {
+            let mut next = Some(meaning.to_string());
+            while let Some(meaning) = next.take() {
+                let word = self.mint(lang, &meaning);
+
+                // A meaning that sorts later gives the word up and has to be minted again
+                let spelling = word.to_string();
+                next = self
+                    .entries
+                    .iter()
+                    .find(|(m, w)| {
+                        **m > meaning && !self.pinned.contains(*m) && w.to_string() == spelling
+                    })
+                    .map(|(m, _)| m.clone());
+
+                self.entries.insert(meaning, word);
+            }
+        }
+
+        &self.entries[meaning]
+    }
+
+    /// Assigns `word` to `meaning`, replacing whatever was there. Words minted afterwards avoid
+    /// pinned words, but a meaning that already had the same word keeps it.
+    pub fn pin(&mut self, meaning: &str, word: Word) -> Option<Word> {
+        self.pinned.insert(meaning.to_string());
+        self.entries.insert(meaning.to_string(), word)
+    }
+
+    #[must_use]
+    pub fn get(&self, meaning: &str) -> Option<&Word> {
+        self.entries.get(meaning)
+    }
+
+    /// Finds the meaning of a word by its spelling.
+    #[must_use]
+    pub fn meaning_of(&self, word: &str) -> Option<&str> {
+        self.entries
+            .iter()
+            .find(|(_, w)| w.to_string() == word)
+            .map(|(m, _)| m.as_str())
+    }
+
+    /// Iterates over entries in alphabetical order of meaning.
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &Word)> {
+        self.entries.iter().map(|(m, w)| (m.as_str(), w))
+    }
+
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Writes one `meaning<TAB>word` line per entry.
+    pub fn export<W: io::Write>(&self, mut writer: W) -> io::Result<()> {
+        for (meaning, word) in &self.entries {
+            writeln!(writer, "{}\t{}", meaning, word)?;
+        }
+
+        Ok(())
+    }
+
+    /// The word for `meaning`, avoiding the words of pinned meanings and of meanings that sort
+    /// before it.
+    fn mint(&self, lang: &SynthLang, meaning: &str) -> Word {
+        let concept = vocabulary::concept(meaning);
+        let word = match concept {
+            Some(concept) => lang.word_for_concept(concept),
+            None => lang.word_for(meaning),
+        };
+        if self.allow_homophones {
+            return word;
+        }
+
+        let taken = |word: &Word| {
+            let spelling = word.to_string();
+            self.entries.iter().any(|(m, w)| {
+                (m.as_str() < meaning || self.pinned.contains(m)) && w.to_string() == spelling
+            })
+        };
+        if !taken(&word) {
+            return word;
+        }
+
+        // Homophones are resolved by salting the key, so the result only depends on the key
+        // and on the meanings that come before it. Concepts keep their usual shape.
+        (1..=ATTEMPTS)
+            .map(|n| match concept {
+                Some(concept) => lang.salted_word_for_concept(concept, n),
+                None => lang.word_for(&format!("{}#{}", meaning, n)),
+            })
+            .find(|w| !taken(w))
+            .unwrap_or(word)
+    }
+}
+
+impl SynthLang {
+    /// The word for a concept from the core vocabulary. Basic concepts tend to get one
+    /// syllable, and concepts with parts are compounded from them in about half of all
+    /// languages.
+    #[must_use]
+    pub fn word_for_concept(&self, concept: &Concept) -> Word {
+        self.salted_word_for_concept(concept, 0)
+    }
+
+    /// Another word shaped like the one for `concept`, with `salt` picking which.
+    pub(crate) fn salted_word_for_concept(&self, concept: &Concept, salt: usize) -> Word {
+        // Blocked words are replaced by salting the key, like in `word_for`
+        self.generate_checked(None, 0, |n| {
+            self.generate_word_for_concept(concept, salt + n)
+        })
+    }
+
+    fn generate_word_for_concept(&self, concept: &Concept, salt: usize) -> Word {
+        if let Some(parent) = &self.parent {
+            return match salt {
+                0 => self.cognate(&parent.word_for_concept(concept)),
+                _ => self.cognate(&parent.generate_word_for_concept(concept, salt)),
+            };
+        }
+
+        let mut rng = self.keyed_rng(Self::salt(Self::hash_key(concept.key), salt));
+
+        if let Some((left, right)) = concept.parts {
+            if rng.gen_bool(0.5) {
+                let part = |key| match vocabulary::concept(key) {
+                    Some(concept) => self.word_for_concept(concept),
+                    None => self.word_for(key),
+                };
+
+                return self.generate_compound(&mut rng, &part(left), &part(right));
+            }
+        }
+
+        if concept.basic {
+            self.generate_word_with_lengths(&mut rng, &[(1, 3), (2, 1)])
+        } else {
+            self.generate_word(&mut rng)
+        }
+    }
+
+    /// A starter dictionary with a word for every concept in the core vocabulary.
+    #[must_use]
+    pub fn dictionary(&self) -> Lexicon {
+        let mut lexicon = Lexicon::new();
+        for concept in CORE_VOCABULARY {
+            lexicon.word(self, concept.key);
+        }
+
+        lexicon
+    }
+
+    /// Hands a parent language's lexicon down to this daughter language, turning every entry
+    /// into its cognate.
+    #[must_use]
+    pub fn inherit_lexicon(&self, lexicon: &Lexicon) -> Lexicon {
+        Lexicon {
+            entries: lexicon
+                .entries
+                .iter()
+                .map(|(m, w)| (m.clone(), self.cognate(w)))
+                .collect(),
+            pinned: lexicon.pinned.clone(),
+            allow_homophones: lexicon.allow_homophones,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MEANINGS: [&str; 6] = ["mountain", "river", "green", "red", "king", "sword"];
+
+    #[test]
+    fn order_independent() {
+        let lang = SynthLang::new(7);
+
+        let mut forwards = Lexicon::new();
+        for m in MEANINGS.iter() {
+            forwards.word(&lang, m);
+        }
+
+        let mut backwards = Lexicon::new();
+        for m in MEANINGS.iter().rev() {
+            backwards.word(&lang, m);
+        }
+
+        for m in MEANINGS.iter() {
+            assert_eq!(
+                forwards.get(m).unwrap().to_string(),
+                backwards.get(m).unwrap().to_string()
+            );
+        }
+    }
+
+    #[test]
+    fn homophones_order_independent() {
+        let lang = SynthLang::new(3);
+        assert_eq!(
+            lang.word_for("k166").to_string(),
+            lang.word_for("k183").to_string()
+        );
+
+        let mut forwards = Lexicon::new();
+        forwards.word(&lang, "k166");
+        forwards.word(&lang, "k183");
+
+        let mut backwards = Lexicon::new();
+        backwards.word(&lang, "k183");
+        backwards.word(&lang, "k166");
+
+        for m in &["k166", "k183"] {
+            assert_eq!(
+                forwards.get(m).unwrap().to_string(),
+                backwards.get(m).unwrap().to_string()
+            );
+        }
+        assert_eq!(
+            forwards.get("k166").unwrap().to_string(),
+            lang.word_for("k166").to_string()
+        );
+        assert_ne!(
+            forwards.get("k183").unwrap().to_string(),
+            forwards.get("k166").unwrap().to_string()
+        );
+    }
+
+    #[test]
+    fn concept_homophones() {
+        let mut resolved = 0;
+        for seed in 0..10 {
+            let lang = SynthLang::new(seed);
+            let dictionary = lang.dictionary();
+
+            for concept in CORE_VOCABULARY {
+                let word = dictionary.get(concept.key).unwrap().to_string();
+                if word == lang.word_for_concept(concept).to_string() {
+                    continue;
+                }
+
+                resolved += 1;
+                assert!((1..20)
+                    .any(|n| { lang.salted_word_for_concept(concept, n).to_string() == word }));
+            }
+        }
+        assert!(resolved > 0);
+    }
+
+    #[test]
+    fn no_homophones() {
+        let lang = SynthLang::new(3);
+        let mut lexicon = Lexicon::new();
+
+        for i in 0..500 {
+            lexicon.word(&lang, &format!("meaning {}", i));
+        }
+
+        let mut spellings: Vec<String> = lexicon.iter().map(|(_, w)| w.to_string()).collect();
+        spellings.sort();
+        spellings.dedup();
+        assert_eq!(spellings.len(), 500);
+
+        let mut backwards = Lexicon::new();
+        for i in (0..500).rev() {
+            backwards.word(&lang, &format!("meaning {}", i));
+        }
+        for (meaning, word) in lexicon.iter() {
+            assert_eq!(
+                backwards.get(meaning).unwrap().to_string(),
+                word.to_string()
+            );
+        }
+    }
+
+    #[test]
+    fn small_language() {
+        let mut lang = SynthLang::new(2);
+        while lang.vowels.len() > 1 {
+            let vowel = lang.vowels[0].clone();
+            lang.remove_phoneme(&vowel);
+        }
+        while lang.consonants.len() > 1 {
+            let consonant = lang.consonants[0].clone();
+            lang.remove_phoneme(&consonant);
+        }
+        lang.vc_weight = 0;
+        lang.cvc_weight = 0;
+
+        // There aren't enough words to go round, so some meanings have to share
+        let mut lexicon = Lexicon::new();
+        for m in MEANINGS.iter() {
+            lexicon.word(&lang, m);
+        }
+        assert_eq!(lexicon.len(), MEANINGS.len());
+    }
+
+    #[test]
+    fn dictionary() {
+        let lang = SynthLang::new(11);
+        let dictionary = lang.dictionary();
+
+        assert_eq!(dictionary.len(), CORE_VOCABULARY.len());
+
+        let mut lexicon = Lexicon::new();
+        assert_eq!(
+            lexicon.word(&lang, "dragon").to_string(),
+            dictionary.get("dragon").unwrap().to_string()
+        );
+
+        let syllables = |field| {
+            let words: Vec<&Word> = vocabulary::field(field)
+                .map(|c| dictionary.get(c.key).unwrap())
+                .collect();
+            words.iter().map(|w| w.parts.len()).sum::<usize>() as f32 / words.len() as f32
+        };
+        assert!(syllables(vocabulary::SemanticField::Body) < 1.6);
+    }
+
+    #[test]
+    fn export() {
+        let lang = SynthLang::new(1);
+        let mut lexicon = Lexicon::new();
+        lexicon.word(&lang, "river");
+        lexicon.word(&lang, "mountain");
+
+        let mut out = vec![];
+        lexicon.export(&mut out).unwrap();
+        let out = String::from_utf8(out).unwrap();
+
+        assert_eq!(out.lines().count(), 2);
+        assert!(out.starts_with("mountain\t"));
+    }
+}
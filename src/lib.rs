@@ -5,38 +5,92 @@ use rand::seq::SliceRandom;
 use rand_pcg::Pcg64;
 use std::collections::HashMap;
 use std::fmt;
+use std::sync::{Arc, OnceLock};
+
+mod borrow;
+mod case;
+mod compare;
+mod family;
+mod filter;
+pub mod grammar;
+mod inventory;
+mod lexicon;
+mod likelihood;
+mod names;
+mod parse;
+pub mod phoneme;
+mod places;
+mod pronounce;
+mod search;
+mod segment;
+mod sonority;
+pub mod sound_change;
+mod stats;
+mod text;
+mod translator;
+mod unique;
+pub mod vocabulary;
+
+pub use case::{Casing, DigraphCase};
+pub use filter::{Blocklist, ContentFilter};
+pub use grammar::{Grammar, NounPhrase, Sentence, Tense};
+pub use lexicon::Lexicon;
+pub use names::{Gender, Name, NameGenerator, NamingConventions, Surnames};
+pub use parse::{IllegalTransition, ParseWordError};
+pub use places::{Descriptor, FeatureType, PlaceNameGenerator};
+pub use search::{SearchResults, SeedSearch};
+pub use sonority::Strictness;
+pub use sound_change::{ParseSoundChangeError, SoundChange, SoundChanges};
+pub use stats::Statistics;
+pub use text::TextGenerator;
+pub use translator::{Translation, Translator};
+pub use unique::{Similarity, UniquenessTracker};
+
+// Number of syllables in a word, and how often each is picked
+const WORD_LENGTHS: &[(usize, i32)] = &[(1, 1), (2, 2)];
+
+// How many times a syllable that would spell a letter three times in a row is picked again
+const SYLLABLE_ATTEMPTS: usize = 20;
 
 type NextPartWeights = HashMap<String, (Vec<(String, i32)>, Vec<(String, i32)>)>;
 
+/// Which syllable a word loses when it's the left or right half of a compound.
 #[allow(clippy::enum_variant_names)]
-#[derive(Debug, Clone)]
-enum CompoundRule {
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CompoundRule {
+    /// The first syllable is dropped.
     DropLeft,
+    /// The last syllable is dropped.
     DropRight,
+    /// The word is kept whole.
     DropNone,
 }
 
-#[derive(Debug, Clone)]
-enum SyllableType {
+/// The shape of a syllable: consonants (C) and vowels (V).
+#[allow(clippy::upper_case_acronyms)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SyllableType {
     VC,
     CV,
     CVC,
 }
 
-#[derive(Debug, Clone)]
-enum SyllablePartType {
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SyllablePartType {
     Consonant,
     Vowel,
 }
 
+/// A single phoneme within a syllable.
 #[derive(Debug, Clone)]
-struct SyllablePart {
+pub struct SyllablePart {
     part_type: SyllablePartType,
     value: String,
 }
 
+/// One syllable of a word: its phonemes, in order.
 #[derive(Debug, Clone)]
-struct Syllable {
+pub struct Syllable {
     parts: Vec<SyllablePart>,
 }
 
@@ -46,7 +100,7 @@ pub struct Word {
     compound_rule: CompoundRule,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct SynthLang {
     pub consonants: Vec<String>,
     pub vowels: Vec<String>,
@@ -54,19 +108,26 @@ pub struct SynthLang {
     pub cv_weight: i32,
     pub cvc_weight: i32,
     next_part_weights: NextPartWeights,
+    // Weights for DropLeft, DropRight and DropNone
+    compound_weights: (i32, i32, i32),
+    parent: Option<Box<SynthLang>>,
+    sound_changes: SoundChanges,
+    seed: u64,
     rng: Pcg64,
+    uniqueness: Option<UniquenessTracker>,
+    content_filter: Option<Arc<dyn ContentFilter>>,
+    min_pronounceability: Option<f32>,
+    sonority: Strictness,
+    // Built on first use, and cleared by anything that changes the language's words
+    casing: OnceLock<Casing>,
 }
 
 impl fmt::Display for Word {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        let mut word = String::new();
-
         for part in &self.parts {
-            let formatted = format!("{}", part);
-            word.push_str(&formatted);
+            write!(f, "{}", part)?;
         }
-
-        write!(f, "{}", SynthLang::remove_repeated_chars(&word))
+        Ok(())
     }
 }
 
@@ -117,7 +178,7 @@ impl SynthLang {
         let mut shuffled: Vec<String> = vowels
             .iter()
             .chain(consonants.iter())
-            .chain(vec!["\0".to_string()].iter())
+            .chain(["\0".to_string()].iter())
             .cloned()
             .collect();
         shuffled.shuffle(&mut rng);
@@ -165,7 +226,16 @@ impl SynthLang {
             vc_weight: weights.1,
             cvc_weight: weights.2,
             next_part_weights,
+            compound_weights: (1, 1, 2),
+            parent: None,
+            sound_changes: SoundChanges::new(),
+            seed,
             rng,
+            uniqueness: None,
+            content_filter: None,
+            min_pronounceability: None,
+            sonority: Strictness::Off,
+            casing: OnceLock::new(),
         }
     }
 
@@ -435,16 +505,14 @@ impl SynthLang {
         *possible_weights.choose(&mut rng).unwrap()
     }
 
-    fn syllable(&mut self) -> Syllable {
+    // `previous` is the syllable this one follows, if any
+    fn syllable(&self, rng: &mut Pcg64, previous: Option<&Syllable>) -> Syllable {
         let choices = [
             (SyllableType::CV, self.cv_weight),
             (SyllableType::VC, self.vc_weight),
             (SyllableType::CVC, self.cvc_weight),
         ];
-        let syllable_type = &choices
-            .choose_weighted(&mut self.rng, |item| item.1)
-            .unwrap()
-            .0;
+        let syllable_type = &choices.choose_weighted(rng, |item| item.1).unwrap().0;
 
         let mut parts = vec![];
 
@@ -452,35 +520,35 @@ impl SynthLang {
             SyllableType::CV => {
                 parts.push(SyllablePart {
                     part_type: SyllablePartType::Consonant,
-                    value: self.next_part(&"\0".to_string(), SyllablePartType::Consonant),
+                    value: self.onset(rng, previous),
                 });
                 parts.push(SyllablePart {
                     part_type: SyllablePartType::Vowel,
-                    value: self.next_part(&parts[0].value, SyllablePartType::Vowel),
+                    value: self.next_part(rng, &parts[0].value, SyllablePartType::Vowel),
                 });
             }
             SyllableType::VC => {
                 parts.push(SyllablePart {
                     part_type: SyllablePartType::Vowel,
-                    value: self.next_part(&"\0".to_string(), SyllablePartType::Vowel),
+                    value: self.next_part(rng, "\0", SyllablePartType::Vowel),
                 });
                 parts.push(SyllablePart {
                     part_type: SyllablePartType::Consonant,
-                    value: self.next_part(&parts[0].value, SyllablePartType::Consonant),
+                    value: self.next_part(rng, &parts[0].value, SyllablePartType::Consonant),
                 });
             }
             SyllableType::CVC => {
                 parts.push(SyllablePart {
                     part_type: SyllablePartType::Consonant,
-                    value: self.next_part(&"\0".to_string(), SyllablePartType::Consonant),
+                    value: self.onset(rng, previous),
                 });
                 parts.push(SyllablePart {
                     part_type: SyllablePartType::Vowel,
-                    value: self.next_part(&parts[0].value, SyllablePartType::Vowel),
+                    value: self.next_part(rng, &parts[0].value, SyllablePartType::Vowel),
                 });
                 parts.push(SyllablePart {
                     part_type: SyllablePartType::Consonant,
-                    value: self.next_part(&parts[1].value, SyllablePartType::Consonant),
+                    value: self.next_part(rng, &parts[1].value, SyllablePartType::Consonant),
                 });
             }
         }
@@ -488,14 +556,14 @@ impl SynthLang {
         Syllable { parts }
     }
 
-    fn next_part(&mut self, part: &str, next_part_type: SyllablePartType) -> String {
+    fn next_part(&self, rng: &mut Pcg64, part: &str, next_part_type: SyllablePartType) -> String {
         match next_part_type {
             SyllablePartType::Vowel => self
                 .next_part_weights
                 .get(part)
                 .unwrap()
                 .0
-                .choose_weighted(&mut self.rng, |c| c.1)
+                .choose_weighted(rng, |c| c.1)
                 .unwrap()
                 .0
                 .clone(),
@@ -504,40 +572,133 @@ impl SynthLang {
                 .get(part)
                 .unwrap()
                 .1
-                .choose_weighted(&mut self.rng, |c| c.1)
+                .choose_weighted(rng, |c| c.1)
                 .unwrap()
                 .0
                 .clone(),
         }
     }
 
+    fn weight(&self, part: &str, next_part: &str) -> i32 {
+        self.next_part_weights
+            .get(part)
+            .and_then(|(vowels, consonants)| {
+                vowels
+                    .iter()
+                    .chain(consonants.iter())
+                    .find(|w| w.0 == next_part)
+            })
+            .map_or(0, |w| w.1)
+    }
+
     pub fn word(&mut self) -> Word {
+        let mut rng = self.rng.clone();
+        let word = if self.checks_words() {
+            self.generate_acceptable_word(&mut rng)
+        } else {
+            self.generate_word(&mut rng)
+        };
+        self.rng = rng;
+
+        if let Some(tracker) = &mut self.uniqueness {
+            tracker.insert(&word);
+        }
+
+        word
+    }
+
+    pub fn compound(&mut self, left: &Word, right: &Word) -> Word {
+        let mut rng = self.rng.clone();
+        let word = if self.checks_words() {
+            self.generate_acceptable_compound(&mut rng, left, right)
+        } else {
+            self.generate_compound(&mut rng, left, right)
+        };
+        self.rng = rng;
+
+        if let Some(tracker) = &mut self.uniqueness {
+            tracker.insert(&word);
+        }
+
+        word
+    }
+
+    /// Generates the word for `key`. The word only depends on this language and the key, not
+    /// on anything else that has been generated, so it can be called lazily, from any thread,
+    /// and always gives the same answer. Daughter languages return the cognate of their
+    /// parent's word for the key.
+    #[must_use]
+    pub fn word_for(&self, key: &str) -> Word {
+        self.word_for_hash(Self::hash_key(key))
+    }
+
+    /// Like `word_for`, but for callers that already have a stable hash of their key.
+    #[must_use]
+    pub fn word_for_hash(&self, hash: u64) -> Word {
+        // Blocked words are replaced by salting the hash, so they stay reproducible
+        self.generate_checked(None, 0, |n| match &self.parent {
+            Some(parent) => self.cognate(&parent.word_for_hash(Self::salt(hash, n))),
+            None => self.generate_word(&mut self.keyed_rng(Self::salt(hash, n))),
+        })
+    }
+
+    /// The seed this language was created from.
+    #[must_use]
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    fn keyed_rng(&self, hash: u64) -> Pcg64 {
+        Pcg64::seed_from_u64(self.seed ^ hash.wrapping_mul(0x9e37_79b9_7f4a_7c15))
+    }
+
+    // FNV-1a, which unlike `DefaultHasher` is guaranteed to stay the same between releases
+    fn hash_key(key: &str) -> u64 {
+        key.bytes().fold(0xcbf2_9ce4_8422_2325, |hash, b| {
+            (hash ^ u64::from(b)).wrapping_mul(0x0100_0000_01b3)
+        })
+    }
+
+    // A different hash for every `n`, except that 0 leaves it as it is
+    fn salt(hash: u64, n: usize) -> u64 {
+        if n == 0 {
+            hash
+        } else {
+            Self::hash_key(&format!("{}#{}", hash, n))
+        }
+    }
+
+    fn generate_word(&self, rng: &mut Pcg64) -> Word {
+        self.generate_word_with_lengths(rng, WORD_LENGTHS)
+    }
+
+    // Lengths are (number of syllables, weight)
+    fn generate_word_with_lengths(&self, rng: &mut Pcg64, lengths: &[(usize, i32)]) -> Word {
         let mut syllables = vec![];
 
-        let choices = [(1, 1), (2, 2)];
-        let num_syllables = choices
-            .choose_weighted(&mut self.rng, |item| item.1)
-            .unwrap()
-            .0;
+        let num_syllables = lengths.choose_weighted(rng, |item| item.1).unwrap().0;
 
+        let mut spelled = String::new();
         for _ in 0..num_syllables {
-            syllables.push(self.syllable());
+            // Otherwise the word would lose a phoneme, and the syllable maybe its shape
+            let mut syllable = self.syllable(rng, syllables.last());
+            for _ in 0..SYLLABLE_ATTEMPTS {
+                if !syllable.repeats_after(&spelled) {
+                    break;
+                }
+                syllable = self.syllable(rng, syllables.last());
+            }
+
+            spelled.push_str(&syllable.to_string());
+            syllables.push(syllable);
         }
 
-        // 50/50 drop something
-        let compound_rule = match self.rng.gen_range(0, 4) {
-            0 => CompoundRule::DropLeft,
-            1 => CompoundRule::DropRight,
-            _ => CompoundRule::DropNone,
-        };
+        let compound_rule = self.random_compound_rule(rng);
 
-        Word {
-            parts: syllables,
-            compound_rule,
-        }
+        self.canonical_word(syllables, compound_rule)
     }
 
-    pub fn compound(&mut self, left: &Word, right: &Word) -> Word {
+    fn generate_compound(&self, rng: &mut Pcg64, left: &Word, right: &Word) -> Word {
         let mut new = vec![];
 
         match left.compound_rule {
@@ -552,58 +713,67 @@ impl SynthLang {
             }
         }
 
-        match right.compound_rule {
-            CompoundRule::DropLeft if right.parts.len() >= 2 => {
-                new.extend(right.parts.iter().skip(1).cloned());
-            }
+        let right_parts = match right.compound_rule {
+            CompoundRule::DropLeft if right.parts.len() >= 2 => &right.parts[1..],
             CompoundRule::DropRight if right.parts.len() >= 2 => {
-                new.extend(right.parts.iter().rev().skip(1).rev().cloned());
-            }
-            _ => {
-                new.extend(right.parts.iter().cloned());
+                &right.parts[..right.parts.len() - 1]
             }
-        }
-
-        // 50/50 drop something
-        let compound_rule = match self.rng.gen_range(0, 4) {
-            0 => CompoundRule::DropLeft,
-            1 => CompoundRule::DropRight,
-            _ => CompoundRule::DropNone,
+            _ => &right.parts[..],
         };
+        self.append_syllables(rng, &mut new, right_parts.iter().cloned());
 
-        Word {
-            parts: new,
-            compound_rule,
-        }
-    }
+        let compound_rule = self.random_compound_rule(rng);
 
-    fn remove_repeated_chars(input: &str) -> String {
-        let mut output = String::new();
-        let mut prev = '\0';
-        let mut count = 1;
+        self.canonical_word(new, compound_rule)
+    }
 
-        for c in input.chars() {
-            if c == prev {
-                count += 1
-            } else {
-                count = 1
-            }
+    fn random_compound_rule(&self, rng: &mut Pcg64) -> CompoundRule {
+        // By default this is 50/50 drop something
+        let (left, right, none) = self.compound_weights;
+        let n = rng.gen_range(0, left + right + none);
 
-            if count <= 2 {
-                output.push(c)
-            }
+        if n < left {
+            CompoundRule::DropLeft
+        } else if n < left + right {
+            CompoundRule::DropRight
+        } else {
+            CompoundRule::DropNone
+        }
+    }
 
-            prev = c
+    /// Returns the features of `phoneme` if it is part of this language's inventory.
+    #[must_use]
+    pub fn features(&self, phoneme: &str) -> Option<phoneme::Features> {
+        if self
+            .vowels
+            .iter()
+            .chain(self.consonants.iter())
+            .any(|p| p == phoneme)
+        {
+            phoneme::features(phoneme)
+        } else {
+            None
         }
+    }
+
+    /// Applies `changes` to `word`. Feature changes (`[stop] > [fricative]`) prefer spellings
+    /// from this language's inventory.
+    #[must_use]
+    pub fn evolve(&self, word: &Word, changes: &SoundChanges) -> Word {
+        let inventory: Vec<String> = self
+            .vowels
+            .iter()
+            .chain(self.consonants.iter())
+            .cloned()
+            .collect();
 
-        output
+        changes.apply_with_inventory(word, &inventory)
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use heck::TitleCase;
 
     #[test]
     fn it_works() {
@@ -611,67 +781,86 @@ mod tests {
 
         println!("{:?}", r);
         println!();
-        println!(
-            "The distinguished language of {}",
-            r.word().to_string().to_title_case()
-        );
+        let name = r.word();
+        println!("The distinguished language of {}", r.capitalize(&name));
+
+        let dictionary = r.dictionary();
 
         println!();
-        println!("{:10}{:10}", r.word().to_string(), "dress");
-        println!("{:10}{:10}", r.word().to_string(), "child");
-        println!("{:10}{:10}", r.word().to_string(), "shoes");
-        println!("{:10}{:10}", r.word().to_string(), "orange");
-        println!("{:10}{:10}", r.word().to_string(), "note");
-        println!("{:10}{:10}", r.word().to_string(), "cake");
-        println!("{:10}{:10}", r.word().to_string(), "soup");
-        println!("{:10}{:10}", r.word().to_string(), "soldier");
-        println!("{:10}{:10}", r.word().to_string(), "reporter");
+        for gloss in &[
+            "child", "fish", "bird", "fire", "water", "king", "sword", "dragon", "warrior",
+        ] {
+            println!(
+                "{:10}{:10}",
+                dictionary.get(gloss).unwrap().to_string(),
+                gloss
+            );
+        }
         println!();
 
-        let green = r.word();
-        let red = r.word();
-        let mountain = r.word();
-        let stream = r.word();
-
-        println!(
-            "{:20}{:10}{:10}{:20}",
-            r.compound(&green, &mountain).to_string().to_title_case(),
-            green.to_string(),
-            mountain.to_string(),
-            "Green Mountain",
-        );
-        println!(
-            "{:20}{:10}{:10}{:20}",
-            r.compound(&green, &stream).to_string().to_title_case(),
-            green.to_string(),
-            stream.to_string(),
-            "Green River",
-        );
-        println!(
-            "{:20}{:10}{:10}{:20}",
-            r.compound(&red, &mountain).to_string().to_title_case(),
-            red.to_string(),
-            mountain.to_string(),
-            "Red Mountain",
-        );
-        println!(
-            "{:20}{:10}{:10}{:20}",
-            r.compound(&red, &stream).to_string().to_title_case(),
-            red.to_string(),
-            stream.to_string(),
-            "Red River",
-        );
+        let casing = r.casing();
+        let mut places = PlaceNameGenerator::with_lexicon(&r, dictionary.clone(), 0);
+        for (color, feature) in &[
+            ("green", FeatureType::Mountain),
+            ("green", FeatureType::River),
+            ("red", FeatureType::Mountain),
+            ("red", FeatureType::River),
+        ] {
+            println!(
+                "{:20}{:10}{:10}{:20}",
+                casing.capitalize(
+                    &places
+                        .name(*feature, &[Descriptor::Meaning(color.to_string())])
+                        .to_string()
+                ),
+                dictionary.get(color).unwrap().to_string(),
+                dictionary.get(feature.meaning()).unwrap().to_string(),
+                casing.title_case(&format!("{} {:?}", color, feature)),
+            );
+        }
         println!();
 
         assert_eq!(2 + 2, 4);
     }
 
     #[test]
-    fn repeats() {
-        let s = "aaa bab cccccc ok".to_string();
-        assert_eq!(
-            SynthLang::remove_repeated_chars(&s),
-            "aa bab cc ok".to_string()
-        );
+    fn word_for_is_order_independent() {
+        let mut lang = SynthLang::new(5);
+        let before = lang.word_for("mountain").to_string();
+
+        for _ in 0..10 {
+            lang.word();
+        }
+
+        assert_eq!(lang.word_for("mountain").to_string(), before);
+        assert_eq!(SynthLang::new(5).word_for("mountain").to_string(), before);
+    }
+
+    #[test]
+    fn word_for_in_parallel() {
+        let lang = std::sync::Arc::new(SynthLang::new(9));
+        let keys: Vec<String> = (0..64).map(|i| format!("npc {}", i)).collect();
+        let expected: Vec<String> = keys.iter().map(|k| lang.word_for(k).to_string()).collect();
+
+        let handles: Vec<_> = keys
+            .chunks(16)
+            .map(|chunk| {
+                let lang = lang.clone();
+                let chunk = chunk.to_vec();
+                std::thread::spawn(move || {
+                    chunk
+                        .iter()
+                        .map(|k| lang.word_for(k).to_string())
+                        .collect::<Vec<_>>()
+                })
+            })
+            .collect();
+
+        let actual: Vec<String> = handles
+            .into_iter()
+            .flat_map(|h| h.join().unwrap())
+            .collect();
+
+        assert_eq!(actual, expected);
     }
 }
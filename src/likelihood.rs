@@ -0,0 +1,178 @@
+//! The probability of a word under a language's generation model, and guessing which of
+//! several languages a word came from.
+
+use crate::{Syllable, SyllablePartType, SynthLang, Word};
+
+impl SynthLang {
+    /// The natural log of the probability that this language generates `word` with exactly
+    /// these syllables, given how many syllables it has. It's `-inf` if the language can't
+    /// generate it at all.
+    ///
+    /// The sonority strictness and any checks on generated words are not taken into account.
+    #[must_use]
+    pub fn log_probability(&self, word: &Word) -> f64 {
+        word.parts
+            .iter()
+            .map(|s| self.syllable_log_probability(s))
+            .sum()
+    }
+
+    /// The log probability of the likeliest way this language could have generated `text`,
+    /// or `-inf` if it couldn't have. Capitals are ignored.
+    #[must_use]
+    pub fn log_likelihood(&self, text: &str) -> f64 {
+        let text = text.to_lowercase();
+        if text.is_empty() {
+            return f64::NEG_INFINITY;
+        }
+
+        // best[i] is the log probability of the likeliest way to generate text[i..]
+        let mut best = vec![f64::NEG_INFINITY; text.len() + 1];
+        best[text.len()] = 0.0;
+        for i in (0..text.len()).rev() {
+            if !text.is_char_boundary(i) {
+                continue;
+            }
+
+            for (syllable, end) in self.syllables_at(&text, i) {
+                let p = self.syllable_log_probability(&syllable) + best[end];
+                if p > best[i] {
+                    best[i] = p;
+                }
+            }
+        }
+
+        best[0]
+    }
+
+    /// How likely it is that each of `languages` produced `text`, assuming they're all equally
+    /// likely to begin with. The probabilities add up to 1, unless none of the languages could
+    /// have produced `text`, in which case they're all 0.
+    pub fn classify<'a, I>(text: &str, languages: I) -> Vec<f64>
+    where
+        I: IntoIterator<Item = &'a SynthLang>,
+    {
+        let log_likelihoods: Vec<f64> = languages
+            .into_iter()
+            .map(|lang| lang.log_likelihood(text))
+            .collect();
+
+        let max = log_likelihoods
+            .iter()
+            .copied()
+            .fold(f64::NEG_INFINITY, f64::max);
+        if max == f64::NEG_INFINITY {
+            return vec![0.0; log_likelihoods.len()];
+        }
+
+        let likelihoods: Vec<f64> = log_likelihoods.iter().map(|l| (l - max).exp()).collect();
+        let total: f64 = likelihoods.iter().sum();
+
+        likelihoods.into_iter().map(|l| l / total).collect()
+    }
+
+    pub(crate) fn syllable_log_probability(&self, syllable: &Syllable) -> f64 {
+        use SyllablePartType::{Consonant, Vowel};
+
+        let shape: Vec<SyllablePartType> = syllable.parts.iter().map(|p| p.part_type).collect();
+        let weight = match shape[..] {
+            [Consonant, Vowel] => self.cv_weight,
+            [Vowel, Consonant] => self.vc_weight,
+            [Consonant, Vowel, Consonant] => self.cvc_weight,
+            _ => 0,
+        };
+        let total = self.cv_weight + self.vc_weight + self.cvc_weight;
+
+        let mut p = ratio(weight, total).ln();
+        let mut previous = "\0";
+        for part in &syllable.parts {
+            p += self
+                .transition_probability(previous, &part.value, part.part_type)
+                .ln();
+            previous = &part.value;
+        }
+
+        p
+    }
+
+    /// The probability that `next` is chosen after `previous` (`"\0"` at the start of a
+    /// syllable), given that a phoneme of its type is needed.
+    pub(crate) fn transition_probability(
+        &self,
+        previous: &str,
+        next: &str,
+        part_type: SyllablePartType,
+    ) -> f64 {
+        let weights = match (self.next_part_weights.get(previous), part_type) {
+            (Some((vowels, _)), SyllablePartType::Vowel) => vowels,
+            (Some((_, consonants)), SyllablePartType::Consonant) => consonants,
+            (None, _) => return 0.0,
+        };
+
+        let total = weights.iter().map(|w| w.1).sum();
+        let weight = weights.iter().find(|w| w.0 == next).map_or(0, |w| w.1);
+
+        ratio(weight, total)
+    }
+}
+
+fn ratio(weight: i32, total: i32) -> f64 {
+    if total > 0 {
+        f64::from(weight) / f64::from(total)
+    } else {
+        0.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn likelihood() {
+        let mut lang = SynthLang::new(8);
+
+        for _ in 0..50 {
+            let word = lang.word();
+            let p = lang.log_probability(&word);
+
+            assert!(p.is_finite() && p < 0.0);
+            assert!(lang.log_likelihood(&word.to_string()) >= p);
+        }
+        assert_eq!(lang.log_likelihood("!"), f64::NEG_INFINITY);
+    }
+
+    #[test]
+    fn generated_words_are_possible() {
+        for seed in 0..300 {
+            let mut lang = SynthLang::new(seed);
+            for _ in 0..30 {
+                let word = lang.word();
+                assert!(lang.log_probability(&word).is_finite(), "{} {}", seed, word);
+            }
+        }
+    }
+
+    #[test]
+    fn classify() {
+        let mut languages: Vec<SynthLang> = (30..33).map(SynthLang::new).collect();
+
+        let mut correct = 0;
+        for i in 0..languages.len() {
+            for _ in 0..50 {
+                let word = languages[i].word().to_string();
+                let scores = SynthLang::classify(&word, &languages);
+
+                assert!((scores.iter().sum::<f64>() - 1.0).abs() < 1e-9);
+                let best = (0..scores.len())
+                    .max_by(|a, b| scores[*a].partial_cmp(&scores[*b]).unwrap())
+                    .unwrap();
+                if best == i {
+                    correct += 1;
+                }
+            }
+        }
+
+        assert!(correct > 120, "{} of 150", correct);
+    }
+}
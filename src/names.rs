@@ -0,0 +1,548 @@
+//! Personal names: given names, family names, patronymics and nicknames that follow a
+//! per-language naming convention.
+
+use crate::grammar::{Affix, Position};
+use crate::{Casing, CompoundRule, Lexicon, SyllablePartType, SynthLang, UniquenessTracker, Word};
+use rand::prelude::*;
+use rand_pcg::Pcg64;
+use std::fmt;
+
+// How many times a taken or unpronounceable name is regenerated before settling for it
+const ATTEMPTS: usize = 100;
+
+// Meanings that family names are built from: a descriptor followed by a place or a trade
+const SURNAME_DESCRIPTORS: &[&str] = &[
+    "red", "black", "white", "green", "gold", "silver", "iron", "stone", "strong", "brave", "wise",
+    "old", "long", "small", "big", "wolf", "raven", "eagle", "bear", "horse", "fire", "winter",
+    "north", "south",
+];
+const SURNAME_HEADS: &[&str] = &[
+    "hill", "river", "ford", "forest", "valley", "lake", "village", "house", "bridge", "gate",
+    "tree", "field", "mountain", "stone", "smith", "warrior", "lord", "hand", "heart", "shield",
+    "spear", "bow", "son", "friend",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Gender {
+    Feminine,
+    Masculine,
+    Neutral,
+}
+
+/// What follows a person's given name.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Surnames {
+    /// An inherited family name.
+    Family,
+    /// A name derived from a parent's given name.
+    Patronymic,
+    /// Both a parent's name and a family name.
+    Both,
+}
+
+/// How a language builds personal names.
+#[derive(Debug, Clone)]
+pub struct NamingConventions {
+    pub feminine: Affix,
+    pub masculine: Affix,
+    pub diminutive: Affix,
+    pub patronymic: Affix,
+    pub matronymic: Affix,
+    pub surnames: Surnames,
+    /// Whether the family name comes before the given name.
+    pub family_first: bool,
+}
+
+/// A full personal name.
+#[derive(Debug, Clone)]
+pub struct Name {
+    pub given: Word,
+    pub patronymic: Option<Word>,
+    pub family: Option<Word>,
+    pub family_first: bool,
+}
+
+impl Name {
+    /// Displays the name capitalized with `casing`, which should be its language's
+    /// `casing()`.
+    #[must_use]
+    pub fn display<'a>(&'a self, casing: &'a Casing) -> impl fmt::Display + 'a {
+        DisplayName { name: self, casing }
+    }
+
+    /// The words of the name, in the order they're written.
+    fn words(&self) -> Vec<&Word> {
+        let mut words = vec![&self.given];
+        words.extend(&self.patronymic);
+        match &self.family {
+            Some(family) if self.family_first => words.insert(0, family),
+            Some(family) => words.push(family),
+            None => {}
+        }
+
+        words
+    }
+
+    /// The whole name as a single word, for uniqueness tracking.
+    fn to_word(&self) -> Word {
+        Word::new(
+            self.words()
+                .into_iter()
+                .flat_map(|w| w.parts.iter().cloned())
+                .collect(),
+            CompoundRule::DropNone,
+        )
+    }
+}
+
+struct DisplayName<'a> {
+    name: &'a Name,
+    casing: &'a Casing,
+}
+
+impl fmt::Display for DisplayName<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let parts: Vec<String> = self
+            .name
+            .words()
+            .iter()
+            .map(|w| self.casing.capitalize(&w.to_string()))
+            .collect();
+        write!(f, "{}", parts.join(" "))
+    }
+}
+
+/// Generates culturally consistent names for a language.
+#[derive(Debug, Clone)]
+pub struct NameGenerator<'a> {
+    lang: &'a SynthLang,
+    conventions: NamingConventions,
+    lexicon: Lexicon,
+    uniqueness: Option<UniquenessTracker>,
+    rng: Pcg64,
+}
+
+impl NamingConventions {
+    fn random(lang: &SynthLang, rng: &mut Pcg64) -> Self {
+        let suffix = |form| Affix {
+            form,
+            position: Position::After,
+        };
+
+        // Feminine names tend to end in vowels, masculine ones in consonants
+        let feminine = Self::ending(lang, rng, SyllablePartType::Vowel, None);
+        let masculine = Self::ending(lang, rng, SyllablePartType::Consonant, Some(&feminine));
+
+        let diminutive = suffix(lang.affix_form(rng, None));
+        let patronymic = Affix {
+            form: lang.affix_form(rng, None),
+            position: if rng.gen_bool(0.2) {
+                Position::Before
+            } else {
+                Position::After
+            },
+        };
+        let matronymic = Affix {
+            form: lang.affix_form(rng, None),
+            position: patronymic.position,
+        };
+
+        let surnames = *[
+            (Surnames::Family, 5),
+            (Surnames::Patronymic, 2),
+            (Surnames::Both, 1),
+        ]
+        .choose_weighted(rng, |s| s.1)
+        .map(|s| &s.0)
+        .unwrap();
+
+        Self {
+            feminine: suffix(feminine),
+            masculine: suffix(masculine),
+            diminutive,
+            patronymic,
+            matronymic,
+            surnames,
+            family_first: rng.gen_bool(0.25),
+        }
+    }
+
+    /// A one-syllable ending that preferably ends in `last`.
+    fn ending(
+        lang: &SynthLang,
+        rng: &mut Pcg64,
+        last: SyllablePartType,
+        not: Option<&Word>,
+    ) -> Word {
+        let mut fallback = None;
+
+        for _ in 0..20 {
+            let word = lang.affix_form(rng, None);
+            if not.is_some_and(|n| n.to_string() == word.to_string()) {
+                continue;
+            }
+
+            let ends_in = word.parts[0].parts.last().map(|p| p.part_type);
+            if ends_in == Some(last) {
+                return word;
+            }
+            fallback.get_or_insert(word);
+        }
+
+        fallback.unwrap_or_else(|| lang.affix_form(rng, None))
+    }
+
+    fn cognate(&self, lang: &SynthLang) -> Self {
+        let mut rng = lang.keyed_rng(SynthLang::hash_key("\0names"));
+        let mut affix = |a: &Affix| Affix {
+            form: lang.affix_form(&mut rng, Some(lang.cognate(&a.form))),
+            position: a.position,
+        };
+
+        Self {
+            feminine: affix(&self.feminine),
+            masculine: affix(&self.masculine),
+            diminutive: affix(&self.diminutive),
+            patronymic: affix(&self.patronymic),
+            matronymic: affix(&self.matronymic),
+            ..self.clone()
+        }
+    }
+}
+
+impl<'a> NameGenerator<'a> {
+    #[must_use]
+    pub fn new(lang: &'a SynthLang, seed: u64) -> Self {
+        Self::with_lexicon(lang, Lexicon::new(), seed)
+    }
+
+    /// A generator that builds family names from the words in `lexicon`, adding any that are
+    /// missing.
+    #[must_use]
+    pub fn with_lexicon(lang: &'a SynthLang, lexicon: Lexicon, seed: u64) -> Self {
+        Self {
+            lang,
+            conventions: lang.naming_conventions(),
+            lexicon,
+            uniqueness: lang.uniqueness_tracker().cloned(),
+            rng: Pcg64::seed_from_u64(seed),
+        }
+    }
+
+    #[must_use]
+    pub fn conventions(&self) -> &NamingConventions {
+        &self.conventions
+    }
+
+    #[must_use]
+    pub fn lexicon(&self) -> &Lexicon {
+        &self.lexicon
+    }
+
+    /// Sets the tracker that names are checked against and added to. It starts out as a copy
+    /// of the language's own tracker, if it has one. `given_name()` and `family_name()` track
+    /// the words they return, and `name()` tracks whole names.
+    pub fn set_uniqueness_tracker(&mut self, tracker: Option<UniquenessTracker>) {
+        self.uniqueness = tracker;
+    }
+
+    #[must_use]
+    pub fn uniqueness_tracker(&self) -> Option<&UniquenessTracker> {
+        self.uniqueness.as_ref()
+    }
+
+    pub fn given_name(&mut self, gender: Gender) -> Word {
+        self.unique(|names| names.generate_given_name(gender))
+    }
+
+    fn generate_given_name(&mut self, gender: Gender) -> Word {
+        self.unblocked(|names| {
+            let root = names
+                .lang
+                .generate_word_with_lengths(&mut names.rng, &[(1, 2), (2, 3)]);
+
+            match gender {
+                Gender::Feminine => names.conventions.feminine.apply(&root),
+                Gender::Masculine => names.conventions.masculine.apply(&root),
+                Gender::Neutral => root,
+            }
+        })
+    }
+
+    /// A family name compounded from a descriptor and a place or trade, like "Blackford" or
+    /// "Ironsmith".
+    pub fn family_name(&mut self) -> Word {
+        self.unique(Self::generate_family_name)
+    }
+
+    fn generate_family_name(&mut self) -> Word {
+        let left = *SURNAME_DESCRIPTORS.choose(&mut self.rng).unwrap();
+        let right = *SURNAME_HEADS
+            .iter()
+            .filter(|h| **h != left)
+            .collect::<Vec<_>>()
+            .choose(&mut self.rng)
+            .unwrap();
+
+        let left = self.lexicon.word(self.lang, left).clone();
+        let right = self.lexicon.word(self.lang, right).clone();
+
+        self.lang.generate_compound(&mut self.rng, &left, &right)
+    }
+
+    /// A name meaning "child of `father`".
+    #[must_use]
+    pub fn patronymic(&self, father: &Word) -> Word {
+        self.lang.inflect(&self.conventions.patronymic, father)
+    }
+
+    #[must_use]
+    pub fn matronymic(&self, mother: &Word) -> Word {
+        self.lang.inflect(&self.conventions.matronymic, mother)
+    }
+
+    /// A pet name: the first syllable of a name with the diminutive suffix. If the content
+    /// filter blocks that, more of the name is kept, and if it blocks every form, there's no
+    /// nickname.
+    #[must_use]
+    pub fn nickname(&self, name: &Word) -> Option<Word> {
+        (1..=name.parts.len())
+            .map(|len| {
+                let short = Word {
+                    parts: name.parts.iter().take(len).cloned().collect(),
+                    compound_rule: name.compound_rule,
+                };
+                self.conventions.diminutive.apply(&short)
+            })
+            .find(|nickname| !self.lang.is_blocked(nickname))
+    }
+
+    /// Calls `generate` until its word is acceptable, the way `SynthLang::word()` checks its
+    /// words, and adds that word to the uniqueness tracker.
+    fn unique<F>(&mut self, mut generate: F) -> Word
+    where
+        F: FnMut(&mut Self) -> Word,
+    {
+        let lang = self.lang;
+        let tracker = self.uniqueness.take();
+        let word = lang.generate_checked(tracker.as_ref(), ATTEMPTS, |_| generate(self));
+        self.uniqueness = tracker;
+
+        if let Some(tracker) = &mut self.uniqueness {
+            tracker.insert(&word);
+        }
+
+        word
+    }
+
+    /// Calls `generate` until the content filter lets its word through.
+    fn unblocked<F>(&mut self, mut generate: F) -> Word
+    where
+        F: FnMut(&mut Self) -> Word,
+    {
+        let lang = self.lang;
+        lang.generate_checked(None, 0, |_| generate(self))
+    }
+
+    /// A full name following the language's conventions.
+    pub fn name(&mut self, gender: Gender) -> Name {
+        // Whole names are checked and tracked as a single word
+        let mut name = None;
+        self.unique(|names| {
+            let generated = names.generate_name(gender);
+            let word = generated.to_word();
+            name = Some(generated);
+            word
+        });
+
+        name.unwrap()
+    }
+
+    fn generate_name(&mut self, gender: Gender) -> Name {
+        let given = self.generate_given_name(gender);
+
+        let patronymic = match self.conventions.surnames {
+            Surnames::Family => None,
+            Surnames::Patronymic | Surnames::Both => {
+                let matronymic = self.rng.gen_bool(0.15);
+                Some(self.unblocked(|names| {
+                    if matronymic {
+                        let mother = names.generate_given_name(Gender::Feminine);
+                        names.matronymic(&mother)
+                    } else {
+                        let father = names.generate_given_name(Gender::Masculine);
+                        names.patronymic(&father)
+                    }
+                }))
+            }
+        };
+
+        let family = match self.conventions.surnames {
+            Surnames::Patronymic => None,
+            Surnames::Family | Surnames::Both => Some(self.unblocked(Self::generate_family_name)),
+        };
+
+        Name {
+            given,
+            patronymic,
+            family,
+            family_first: self.conventions.family_first,
+        }
+    }
+}
+
+impl SynthLang {
+    /// This language's naming conventions, derived from its seed. Daughter languages keep
+    /// their parent's conventions, with cognate affixes.
+    #[must_use]
+    pub fn naming_conventions(&self) -> NamingConventions {
+        match &self.parent {
+            Some(parent) => parent.naming_conventions().cognate(self),
+            None => NamingConventions::random(self, &mut self.keyed_rng(Self::hash_key("\0names"))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn names() {
+        for seed in 0..20 {
+            let lang = SynthLang::new(seed);
+            let mut names = NameGenerator::new(&lang, 1);
+            let conventions = names.conventions().clone();
+
+            let feminine = conventions.feminine.form.to_string();
+            for _ in 0..10 {
+                let name = names.given_name(Gender::Feminine);
+                let last = name.parts.last().unwrap();
+                assert_eq!(
+                    Word {
+                        parts: vec![last.clone()],
+                        compound_rule: name.compound_rule
+                    }
+                    .to_string(),
+                    feminine
+                );
+            }
+
+            let name = names.name(Gender::Masculine);
+            assert_eq!(
+                name.patronymic.is_some(),
+                conventions.surnames != Surnames::Family
+            );
+            assert_eq!(
+                name.family.is_some(),
+                conventions.surnames != Surnames::Patronymic
+            );
+
+            let full = name.display(&lang.casing()).to_string();
+            assert!(full
+                .split(' ')
+                .all(|p| p.chars().next().unwrap().is_uppercase()));
+        }
+    }
+
+    #[test]
+    fn filtered() {
+        let mut lang = SynthLang::new(8);
+        let unfiltered: Vec<Word> = {
+            let mut names = NameGenerator::new(&lang, 3);
+            (0..20).map(|_| names.given_name(Gender::Neutral)).collect()
+        };
+
+        let mut blocklist = crate::Blocklist::empty();
+        blocklist.add_word(&unfiltered[4].to_string());
+        blocklist.add_word(
+            &NameGenerator::new(&lang, 3)
+                .nickname(&unfiltered[0])
+                .unwrap()
+                .to_string(),
+        );
+        lang.set_content_filter(Some(std::sync::Arc::new(blocklist)));
+
+        let mut names = NameGenerator::new(&lang, 3);
+        let filtered: Vec<Word> = (0..20).map(|_| names.given_name(Gender::Neutral)).collect();
+        assert_eq!(filtered[3].to_string(), unfiltered[3].to_string());
+        assert_ne!(filtered[4].to_string(), unfiltered[4].to_string());
+        assert!(!lang.is_blocked(&names.nickname(&filtered[0]).unwrap()));
+
+        // A filter that blocks every form of a name leaves it without a nickname
+        let mut blocklist = crate::Blocklist::empty();
+        blocklist.add_substring(&filtered[0].parts[0].to_string());
+        lang.set_content_filter(Some(std::sync::Arc::new(blocklist)));
+        assert!(NameGenerator::new(&lang, 3)
+            .nickname(&filtered[0])
+            .is_none());
+    }
+
+    #[test]
+    fn unique() {
+        let lang = SynthLang::new(8);
+        let casing = lang.casing();
+        let mut names = NameGenerator::new(&lang, 3);
+        names.set_uniqueness_tracker(Some(UniquenessTracker::new(crate::Similarity::Spelling, 1)));
+
+        let full: Vec<String> = (0..300)
+            .map(|_| names.name(Gender::Feminine).display(&casing).to_string())
+            .collect();
+        let given: Vec<String> = (0..300)
+            .map(|_| names.given_name(Gender::Feminine).to_string())
+            .collect();
+        for list in &[full, given] {
+            let mut distinct = list.clone();
+            distinct.sort();
+            distinct.dedup();
+            assert_eq!(distinct.len(), list.len());
+        }
+    }
+
+    #[test]
+    fn casing() {
+        let lang = SynthLang::new(8);
+        let casing = lang.casing();
+        assert_eq!(casing.digraphs, crate::DigraphCase::All);
+        let mut generator = NameGenerator::new(&lang, 0);
+
+        let names: Vec<Name> = (0..20).map(|_| generator.name(Gender::Feminine)).collect();
+        assert!(names.iter().any(|n| {
+            let given = n.given.to_string();
+            lang.capitalize(&n.given) != Casing::default().capitalize(&given)
+        }));
+        for name in &names {
+            let text = name.display(&casing).to_string();
+            assert!(text.contains(&lang.capitalize(&name.given)), "{}", text);
+        }
+
+        let given = names[0].given.to_string();
+        assert!(names[0]
+            .display(&Casing::default())
+            .to_string()
+            .contains(&Casing::default().capitalize(&given)));
+    }
+
+    #[test]
+    fn deterministic() {
+        let lang = SynthLang::new(8);
+        let casing = lang.casing();
+        let a: Vec<String> = {
+            let mut names = NameGenerator::new(&lang, 3);
+            (0..20)
+                .map(|_| names.name(Gender::Neutral).display(&casing).to_string())
+                .collect()
+        };
+        let b: Vec<String> = {
+            let mut names = NameGenerator::new(&lang, 3);
+            (0..20)
+                .map(|_| names.name(Gender::Neutral).display(&casing).to_string())
+                .collect()
+        };
+        assert_eq!(a, b);
+
+        let names = NameGenerator::new(&lang, 3);
+        let nickname = names.nickname(&lang.word_for("aldric")).unwrap();
+        assert_eq!(nickname.parts.len(), 2);
+    }
+}
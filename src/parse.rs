@@ -0,0 +1,307 @@
+//! Reading a spelled word back into phonemes and syllables, e.g. to check a hand-written name
+//! against a language.
+
+use crate::{CompoundRule, Syllable, SyllablePart, SyllablePartType, SynthLang, Word};
+use std::error::Error;
+use std::fmt;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseWordError {
+    Empty,
+    /// Nothing in the language's inventory is spelled like the text at this byte offset of
+    /// the parsed string.
+    UnknownPhoneme(usize),
+    /// The phonemes can't be split into syllables of the shapes the language uses.
+    NoSyllables,
+}
+
+impl fmt::Display for ParseWordError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ParseWordError::Empty => write!(f, "empty word"),
+            ParseWordError::UnknownPhoneme(i) => write!(f, "unknown phoneme at byte {}", i),
+            ParseWordError::NoSyllables => write!(f, "can't be split into syllables"),
+        }
+    }
+}
+
+impl Error for ParseWordError {}
+
+/// A transition a language never generates: `phoneme` after `after`, or at the start of a
+/// syllable when `after` is `None`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IllegalTransition {
+    pub after: Option<String>,
+    pub phoneme: String,
+}
+
+impl fmt::Display for IllegalTransition {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match &self.after {
+            Some(after) => write!(f, "`{}` never follows `{}`", self.phoneme, after),
+            None => write!(f, "`{}` never starts a syllable", self.phoneme),
+        }
+    }
+}
+
+// (illegal transitions, phonemes, syllables), compared in that order
+type Cost = (usize, usize, usize);
+
+impl SynthLang {
+    /// Splits a spelled word into this language's phonemes and syllables. Capitals are
+    /// ignored, and where the spelling is ambiguous the split that follows the language's
+    /// transitions with the fewest phonemes wins, so "sh" is read as one phoneme if it can be.
+    ///
+    /// The word is kept whole when compounded. It may use transitions the language never
+    /// generates; `is_legal` and `illegal_transitions_in` tell whether it does.
+    ///
+    /// # Errors
+    ///
+    /// Fails if the text isn't made of this language's phonemes or can't be split into its
+    /// syllable shapes.
+    pub fn parse(&self, text: &str) -> Result<Word, ParseWordError> {
+        let lowercase = text.to_lowercase();
+        if lowercase.is_empty() {
+            return Err(ParseWordError::Empty);
+        }
+
+        if let Some(i) = self.unknown_phoneme(&lowercase) {
+            return Err(ParseWordError::UnknownPhoneme(original_offset(text, i)));
+        }
+
+        let syllables = self
+            .syllabify_text(&lowercase)
+            .ok_or(ParseWordError::NoSyllables)?;
+
+        Ok(Word::new(syllables, CompoundRule::DropNone))
+    }
+
+    /// Splits lowercase `text` into syllables the way `parse` does, if it can be.
+    pub(crate) fn syllabify_text(&self, text: &str) -> Option<Vec<Syllable>> {
+        // best[i] is the cheapest way to syllabify text[i..], with the syllable starting at i
+        let mut best: Vec<Option<(Cost, Syllable, usize)>> = vec![None; text.len() + 1];
+        for i in (0..text.len()).rev() {
+            if !text.is_char_boundary(i) {
+                continue;
+            }
+
+            for (syllable, end) in self.syllables_at(text, i) {
+                let rest = if end == text.len() {
+                    (0, 0, 0)
+                } else {
+                    match &best[end] {
+                        Some((cost, _, _)) => *cost,
+                        None => continue,
+                    }
+                };
+
+                let cost = (
+                    rest.0 + self.illegal_transitions(&syllable).count(),
+                    rest.1 + syllable.parts.len(),
+                    rest.2 + 1,
+                );
+                if best[i].as_ref().map_or(true, |(b, _, _)| cost < *b) {
+                    best[i] = Some((cost, syllable, end));
+                }
+            }
+        }
+
+        let mut syllables = vec![];
+        let mut i = 0;
+        while i < text.len() {
+            let (_, syllable, end) = best[i].take()?;
+            syllables.push(syllable);
+            i = end;
+        }
+
+        Some(syllables)
+    }
+
+    /// Whether every syllable of `word` has one of this language's shapes and only uses
+    /// transitions this language generates.
+    #[must_use]
+    pub fn is_legal(&self, word: &Word) -> bool {
+        let templates = self.templates();
+
+        word.parts.iter().all(|s| {
+            templates.iter().any(|t| {
+                t.len() == s.parts.len() && t.iter().zip(&s.parts).all(|(a, b)| *a == b.part_type)
+            }) && self.illegal_transitions(s).next().is_none()
+        })
+    }
+
+    /// Every transition in `word` that this language never generates, in order.
+    #[must_use]
+    pub fn illegal_transitions_in(&self, word: &Word) -> Vec<IllegalTransition> {
+        word.parts
+            .iter()
+            .flat_map(|s| self.illegal_transitions(s))
+            .map(|(after, phoneme)| IllegalTransition {
+                after: after.map(ToString::to_string),
+                phoneme: phoneme.to_string(),
+            })
+            .collect()
+    }
+
+    /// If `text` can't be read as phonemes, the byte offset where reading it gets stuck: the
+    /// furthest any way of reading it gets.
+    fn unknown_phoneme(&self, text: &str) -> Option<usize> {
+        let mut reachable = vec![false; text.len() + 1];
+        reachable[0] = true;
+
+        for i in 0..text.len() {
+            if !reachable[i] {
+                continue;
+            }
+            for p in self.consonants.iter().chain(self.vowels.iter()) {
+                if text[i..].starts_with(p.as_str()) {
+                    reachable[i + p.len()] = true;
+                }
+            }
+        }
+
+        if reachable[text.len()] {
+            None
+        } else {
+            reachable.iter().rposition(|r| *r)
+        }
+    }
+
+    /// Every way a syllable can start at byte `start` of `text`, with where it ends.
+    pub(crate) fn syllables_at(&self, text: &str, start: usize) -> Vec<(Syllable, usize)> {
+        let mut found = vec![];
+
+        for template in self.templates() {
+            let mut partial = vec![(vec![], start)];
+            for part_type in template {
+                let candidates = match part_type {
+                    SyllablePartType::Vowel => &self.vowels,
+                    SyllablePartType::Consonant => &self.consonants,
+                };
+
+                partial = partial
+                    .into_iter()
+                    .flat_map(|(parts, i)| {
+                        candidates
+                            .iter()
+                            .filter(move |c| text[i..].starts_with(c.as_str()))
+                            .map(move |c| {
+                                let mut parts: Vec<SyllablePart> = parts.clone();
+                                parts.push(SyllablePart {
+                                    part_type,
+                                    value: c.clone(),
+                                });
+                                (parts, i + c.len())
+                            })
+                    })
+                    .collect();
+            }
+
+            found.extend(
+                partial
+                    .into_iter()
+                    .map(|(parts, end)| (Syllable { parts }, end)),
+            );
+        }
+
+        found
+    }
+
+    /// Pairs of (previous phoneme, phoneme) in `syllable` that this language never generates.
+    pub(crate) fn illegal_transitions<'a>(
+        &'a self,
+        syllable: &'a Syllable,
+    ) -> impl Iterator<Item = (Option<&'a str>, &'a str)> + 'a {
+        let previous = std::iter::once(None).chain(syllable.parts.iter().map(|p| Some(&p.value)));
+
+        previous
+            .zip(syllable.parts.iter())
+            .filter(move |(prev, part)| {
+                self.weight(prev.map_or("\0", String::as_str), &part.value) == 0
+            })
+            .map(|(prev, part)| (prev.map(String::as_str), part.value.as_str()))
+    }
+}
+
+/// The byte offset in `text` of the character that byte `offset` of its lowercase form comes
+/// from. Lowercasing can change how many bytes a character takes.
+fn original_offset(text: &str, offset: usize) -> usize {
+    let mut lowercase = 0;
+    for (i, c) in text.char_indices() {
+        lowercase += c.to_lowercase().map(char::len_utf8).sum::<usize>();
+        if lowercase > offset {
+            return i;
+        }
+    }
+
+    text.len()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trip() {
+        for seed in 0..300 {
+            let mut lang = SynthLang::new(seed);
+
+            for _ in 0..10 {
+                let word = lang.word();
+                let spelled = word.to_string();
+                let parsed = lang.parse(&spelled).unwrap();
+
+                assert_eq!(parsed.to_string(), spelled);
+                assert!(lang.is_legal(&parsed));
+            }
+        }
+    }
+
+    #[test]
+    fn errors() {
+        let mut lang = SynthLang::new(5);
+        let word = lang.word();
+        let spelled = lang.capitalize(&word);
+
+        assert_eq!(lang.parse(&spelled).unwrap().to_string(), word.to_string());
+        assert_eq!(lang.parse("").unwrap_err(), ParseWordError::Empty);
+        assert_eq!(
+            lang.parse(&format!("{}!", word)).unwrap_err(),
+            ParseWordError::UnknownPhoneme(word.to_string().len())
+        );
+
+        // The Kelvin sign is three bytes, but its lowercase "k" is one
+        lang.add_phoneme("k", SyllablePartType::Consonant);
+        assert_eq!(
+            lang.parse(&format!("{}\u{212a}!", word)).unwrap_err(),
+            ParseWordError::UnknownPhoneme(word.to_string().len() + 3)
+        );
+    }
+
+    #[test]
+    fn illegal() {
+        let mut lang = SynthLang::new(5);
+        let word = lang.word();
+        let first = word.phonemes().next().unwrap().to_string();
+        assert!(lang.is_legal(&word));
+
+        for phoneme in lang.consonants.clone().iter().chain(&lang.vowels.clone()) {
+            if *phoneme != first {
+                lang.set_transition_weight("\0", phoneme, 1);
+            }
+        }
+        assert!(lang.set_transition_weight("\0", &first, 0));
+
+        let parsed = lang.parse(&word.to_string()).unwrap();
+        assert_eq!(parsed.to_string(), word.to_string());
+        assert!(!lang.is_legal(&parsed));
+        let illegal = lang.illegal_transitions_in(&parsed);
+        assert_eq!(
+            illegal[0],
+            IllegalTransition {
+                after: None,
+                phoneme: first
+            }
+        );
+    }
+}
@@ -0,0 +1,731 @@
+//! A small articulatory feature model for the phonemes SynthLang knows how to spell.
+
+use std::fmt;
+use std::str::FromStr;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Place {
+    Labial,
+    Dental,
+    Alveolar,
+    PostAlveolar,
+    Palatal,
+    Velar,
+    Uvular,
+    Glottal,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Manner {
+    Stop,
+    Affricate,
+    Fricative,
+    Nasal,
+    Lateral,
+    Rhotic,
+    Approximant,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Height {
+    High,
+    Mid,
+    Low,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Backness {
+    Front,
+    Central,
+    Back,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Features {
+    Consonant {
+        place: Place,
+        manner: Manner,
+        voiced: bool,
+    },
+    Vowel {
+        height: Height,
+        backness: Backness,
+        rounded: bool,
+        diphthong: bool,
+    },
+}
+
+/// A single feature, as written inside brackets in sound change notation (`[front]`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Feature {
+    Consonant,
+    Vowel,
+    Sonorant,
+    Obstruent,
+    Place(Place),
+    Manner(Manner),
+    Voiced,
+    Height(Height),
+    Backness(Backness),
+    Rounded,
+    Diphthong,
+}
+
+const fn consonant(place: Place, manner: Manner, voiced: bool) -> Features {
+    Features::Consonant {
+        place,
+        manner,
+        voiced,
+    }
+}
+
+const fn vowel(height: Height, backness: Backness, rounded: bool) -> Features {
+    Features::Vowel {
+        height,
+        backness,
+        rounded,
+        diphthong: false,
+    }
+}
+
+// Plain spellings come first so that feature lookups prefer them over spice and IPA.
+const PHONEMES: &[(&str, Features)] = &[
+    ("p", consonant(Place::Labial, Manner::Stop, false)),
+    ("b", consonant(Place::Labial, Manner::Stop, true)),
+    ("t", consonant(Place::Alveolar, Manner::Stop, false)),
+    ("d", consonant(Place::Alveolar, Manner::Stop, true)),
+    ("k", consonant(Place::Velar, Manner::Stop, false)),
+    ("g", consonant(Place::Velar, Manner::Stop, true)),
+    ("c", consonant(Place::Velar, Manner::Stop, false)),
+    ("q", consonant(Place::Uvular, Manner::Stop, false)),
+    (
+        "ch",
+        consonant(Place::PostAlveolar, Manner::Affricate, false),
+    ),
+    ("j", consonant(Place::PostAlveolar, Manner::Affricate, true)),
+    ("f", consonant(Place::Labial, Manner::Fricative, false)),
+    ("v", consonant(Place::Labial, Manner::Fricative, true)),
+    ("th", consonant(Place::Dental, Manner::Fricative, false)),
+    ("s", consonant(Place::Alveolar, Manner::Fricative, false)),
+    ("z", consonant(Place::Alveolar, Manner::Fricative, true)),
+    (
+        "sh",
+        consonant(Place::PostAlveolar, Manner::Fricative, false),
+    ),
+    (
+        "zh",
+        consonant(Place::PostAlveolar, Manner::Fricative, true),
+    ),
+    ("x", consonant(Place::Velar, Manner::Fricative, false)),
+    ("h", consonant(Place::Glottal, Manner::Fricative, false)),
+    ("m", consonant(Place::Labial, Manner::Nasal, true)),
+    ("n", consonant(Place::Alveolar, Manner::Nasal, true)),
+    ("ng", consonant(Place::Velar, Manner::Nasal, true)),
+    ("l", consonant(Place::Alveolar, Manner::Lateral, true)),
+    ("r", consonant(Place::Alveolar, Manner::Rhotic, true)),
+    ("w", consonant(Place::Labial, Manner::Approximant, true)),
+    ("y", consonant(Place::Palatal, Manner::Approximant, true)),
+    // Spice
+    ("ñ", consonant(Place::Palatal, Manner::Nasal, true)),
+    ("ń", consonant(Place::Palatal, Manner::Nasal, true)),
+    ("ŋ", consonant(Place::Velar, Manner::Nasal, true)),
+    ("ç", consonant(Place::Palatal, Manner::Fricative, false)),
+    ("ð", consonant(Place::Dental, Manner::Fricative, true)),
+    (
+        "š",
+        consonant(Place::PostAlveolar, Manner::Fricative, false),
+    ),
+    ("ś", consonant(Place::Palatal, Manner::Fricative, false)),
+    ("đ", consonant(Place::Palatal, Manner::Affricate, true)),
+    ("ġ", consonant(Place::PostAlveolar, Manner::Affricate, true)),
+    ("ł", consonant(Place::Velar, Manner::Lateral, true)),
+    ("ŕ", consonant(Place::Alveolar, Manner::Rhotic, true)),
+    ("ĥ", consonant(Place::Velar, Manner::Fricative, false)),
+    ("ĵ", consonant(Place::PostAlveolar, Manner::Fricative, true)),
+    ("ć", consonant(Place::Palatal, Manner::Affricate, false)),
+    (
+        "ĉ",
+        consonant(Place::PostAlveolar, Manner::Affricate, false),
+    ),
+    ("ź", consonant(Place::Palatal, Manner::Fricative, true)),
+    ("ż", consonant(Place::PostAlveolar, Manner::Fricative, true)),
+    ("ẅ", consonant(Place::Labial, Manner::Approximant, true)),
+    ("ŵ", consonant(Place::Labial, Manner::Approximant, true)),
+    ("и", consonant(Place::Palatal, Manner::Approximant, true)),
+    ("й", consonant(Place::Palatal, Manner::Approximant, true)),
+    // Spellings that only appear as the output of sound changes
+    ("ts", consonant(Place::Alveolar, Manner::Affricate, false)),
+    ("dz", consonant(Place::Alveolar, Manner::Affricate, true)),
+    ("dh", consonant(Place::Dental, Manner::Fricative, true)),
+    ("gh", consonant(Place::Velar, Manner::Fricative, true)),
+    ("bh", consonant(Place::Labial, Manner::Approximant, true)),
+    ("ny", consonant(Place::Palatal, Manner::Nasal, true)),
+    ("ky", consonant(Place::Palatal, Manner::Stop, false)),
+    ("gy", consonant(Place::Palatal, Manner::Stop, true)),
+    ("hh", consonant(Place::Uvular, Manner::Fricative, false)),
+    ("'", consonant(Place::Glottal, Manner::Stop, false)),
+    (
+        "tʃ",
+        consonant(Place::PostAlveolar, Manner::Affricate, false),
+    ),
+    (
+        "dʒ",
+        consonant(Place::PostAlveolar, Manner::Affricate, true),
+    ),
+    (
+        "ʃ",
+        consonant(Place::PostAlveolar, Manner::Fricative, false),
+    ),
+    ("ʒ", consonant(Place::PostAlveolar, Manner::Fricative, true)),
+    ("θ", consonant(Place::Dental, Manner::Fricative, false)),
+    ("β", consonant(Place::Labial, Manner::Fricative, true)),
+    ("ɣ", consonant(Place::Velar, Manner::Fricative, true)),
+    ("χ", consonant(Place::Uvular, Manner::Fricative, false)),
+    ("ʔ", consonant(Place::Glottal, Manner::Stop, false)),
+    ("a", vowel(Height::Low, Backness::Central, false)),
+    ("e", vowel(Height::Mid, Backness::Front, false)),
+    ("i", vowel(Height::High, Backness::Front, false)),
+    ("o", vowel(Height::Mid, Backness::Back, true)),
+    ("u", vowel(Height::High, Backness::Back, true)),
+    ("æ", vowel(Height::Low, Backness::Front, false)),
+    ("œ", vowel(Height::Mid, Backness::Front, true)),
+    // Spice
+    ("à", vowel(Height::Low, Backness::Central, false)),
+    ("á", vowel(Height::Low, Backness::Central, false)),
+    ("â", vowel(Height::Low, Backness::Central, false)),
+    ("ã", vowel(Height::Low, Backness::Central, false)),
+    ("ä", vowel(Height::Low, Backness::Front, false)),
+    ("å", vowel(Height::Low, Backness::Back, true)),
+    ("ā", vowel(Height::Low, Backness::Central, false)),
+    ("ă", vowel(Height::Low, Backness::Central, false)),
+    ("è", vowel(Height::Mid, Backness::Front, false)),
+    ("é", vowel(Height::Mid, Backness::Front, false)),
+    ("ê", vowel(Height::Mid, Backness::Front, false)),
+    ("ë", vowel(Height::Mid, Backness::Central, false)),
+    ("ē", vowel(Height::Mid, Backness::Front, false)),
+    ("ĕ", vowel(Height::Mid, Backness::Front, false)),
+    ("ė", vowel(Height::Mid, Backness::Front, false)),
+    ("ě", vowel(Height::Mid, Backness::Front, false)),
+    ("ì", vowel(Height::High, Backness::Front, false)),
+    ("í", vowel(Height::High, Backness::Front, false)),
+    ("î", vowel(Height::High, Backness::Front, false)),
+    ("ï", vowel(Height::High, Backness::Front, false)),
+    ("ĩ", vowel(Height::High, Backness::Front, false)),
+    ("ī", vowel(Height::High, Backness::Front, false)),
+    ("ĭ", vowel(Height::High, Backness::Front, false)),
+    ("ò", vowel(Height::Mid, Backness::Back, true)),
+    ("ó", vowel(Height::Mid, Backness::Back, true)),
+    ("ô", vowel(Height::Mid, Backness::Back, true)),
+    ("õ", vowel(Height::Mid, Backness::Back, true)),
+    ("ö", vowel(Height::Mid, Backness::Front, true)),
+    ("ō", vowel(Height::Mid, Backness::Back, true)),
+    ("ŏ", vowel(Height::Mid, Backness::Back, true)),
+    ("ő", vowel(Height::Mid, Backness::Front, true)),
+    ("ø", vowel(Height::Mid, Backness::Front, true)),
+    ("ù", vowel(Height::High, Backness::Back, true)),
+    ("ú", vowel(Height::High, Backness::Back, true)),
+    ("û", vowel(Height::High, Backness::Back, true)),
+    ("ü", vowel(Height::High, Backness::Front, true)),
+    ("ũ", vowel(Height::High, Backness::Back, true)),
+    ("ū", vowel(Height::High, Backness::Back, true)),
+    ("ŭ", vowel(Height::High, Backness::Back, true)),
+    ("ů", vowel(Height::High, Backness::Back, true)),
+    ("ű", vowel(Height::High, Backness::Front, true)),
+    // Spellings that only appear as the output of sound changes
+    ("ə", vowel(Height::Mid, Backness::Central, false)),
+    ("ɛ", vowel(Height::Mid, Backness::Front, false)),
+    ("ɔ", vowel(Height::Mid, Backness::Back, true)),
+];
+
+/// Returns the features of a phoneme, or `None` if the spelling is not known.
+///
+/// Two plain vowels written together (`ae`, `ou`, `aa`) are treated as a diphthong with the
+/// features of the first vowel.
+#[must_use]
+pub fn features(phoneme: &str) -> Option<Features> {
+    if let Some((_, f)) = PHONEMES.iter().find(|(p, _)| *p == phoneme) {
+        return Some(*f);
+    }
+
+    let mut chars = phoneme.chars();
+    match (chars.next(), chars.next(), chars.next()) {
+        (Some(first), Some(second), None) if is_plain_vowel(first) && is_plain_vowel(second) => {
+            match features(&first.to_string()) {
+                Some(Features::Vowel {
+                    height,
+                    backness,
+                    rounded,
+                    ..
+                }) => Some(Features::Vowel {
+                    height,
+                    backness,
+                    rounded,
+                    diphthong: true,
+                }),
+                _ => None,
+            }
+        }
+        _ => None,
+    }
+}
+
+/// Finds a spelling for a bundle of features, trying `preferred` before the built-in table.
+#[must_use]
+pub fn find(features: Features, preferred: &[String]) -> Option<String> {
+    preferred
+        .iter()
+        .map(String::as_str)
+        .chain(PHONEMES.iter().map(|(p, _)| *p))
+        .find(|p| self::features(p) == Some(features))
+        .map(ToString::to_string)
+}
+
+/// A rough measure of how different two phonemes sound. Zero means identical features.
+#[must_use]
+pub fn distance(a: Features, b: Features) -> u32 {
+    fn diff<T: Copy + PartialEq>(a: T, b: T, ordinal: fn(T) -> u32) -> u32 {
+        ordinal(a).abs_diff(ordinal(b))
+    }
+
+    match (a, b) {
+        (
+            Features::Consonant {
+                place: pa,
+                manner: ma,
+                voiced: va,
+            },
+            Features::Consonant {
+                place: pb,
+                manner: mb,
+                voiced: vb,
+            },
+        ) => diff(pa, pb, |p| p as u32) + 2 * diff(ma, mb, |m| m as u32) + u32::from(va != vb),
+        (
+            Features::Vowel {
+                height: ha,
+                backness: ba,
+                rounded: ra,
+                diphthong: da,
+            },
+            Features::Vowel {
+                height: hb,
+                backness: bb,
+                rounded: rb,
+                diphthong: db,
+            },
+        ) => {
+            diff(ha, hb, |h| h as u32)
+                + diff(ba, bb, |b| b as u32)
+                + u32::from(ra != rb)
+                + u32::from(da != db)
+        }
+        (_, _) => 20,
+    }
+}
+
+/// Returns the IPA transcription of a phoneme, or `None` if the spelling is not known.
+///
+/// Diphthongs are transcribed vowel by vowel, and a doubled vowel (`aa`) as a long one.
+#[must_use]
+pub fn ipa(phoneme: &str) -> Option<String> {
+    let mut chars = phoneme.chars();
+    if let (Some(first), Some(second), None) = (chars.next(), chars.next(), chars.next()) {
+        if is_plain_vowel(first) && is_plain_vowel(second) {
+            let first = features(&first.to_string())?.ipa();
+            let second = features(&second.to_string())?.ipa();
+            if first == second {
+                return Some(format!("{}ː", first));
+            }
+            return Some(format!("{}{}", first, second));
+        }
+    }
+
+    features(phoneme).map(|f| f.ipa().to_string())
+}
+
+fn is_plain_vowel(c: char) -> bool {
+    matches!(c, 'a' | 'e' | 'i' | 'o' | 'u')
+}
+
+impl Features {
+    #[must_use]
+    pub fn is_vowel(&self) -> bool {
+        matches!(self, Features::Vowel { .. })
+    }
+
+    /// The IPA symbol for these features. Diphthongs only have the features of their first
+    /// vowel, so see [`ipa`] for transcribing a spelling.
+    #[must_use]
+    pub fn ipa(&self) -> &'static str {
+        match *self {
+            Features::Consonant {
+                place,
+                manner,
+                voiced,
+            } => match (place, manner, voiced) {
+                (Place::Labial, Manner::Stop, false) => "p",
+                (Place::Labial, Manner::Stop, true) => "b",
+                (Place::Labial, Manner::Affricate, false) => "pf",
+                (Place::Labial, Manner::Affricate, true) => "bv",
+                (Place::Labial, Manner::Fricative, false) => "f",
+                (Place::Labial, Manner::Fricative, true) => "v",
+                (Place::Labial, Manner::Nasal, _) => "m",
+                (Place::Labial, Manner::Rhotic, _) => "ʙ",
+                (Place::Labial, _, _) => "w",
+                (Place::Dental, Manner::Stop, false) => "t̪",
+                (Place::Dental, Manner::Stop, true) => "d̪",
+                (Place::Dental, Manner::Affricate, false) => "t̪θ",
+                (Place::Dental, Manner::Affricate, true) => "d̪ð",
+                (Place::Dental, Manner::Fricative, false) => "θ",
+                (Place::Dental, Manner::Fricative, true) => "ð",
+                (Place::Dental, Manner::Nasal, _) => "n̪",
+                (Place::Dental, Manner::Lateral, _) => "l̪",
+                (Place::Dental, Manner::Rhotic, _) => "r̪",
+                (Place::Dental, Manner::Approximant, _) => "ð̞",
+                (Place::Alveolar, Manner::Stop, false) => "t",
+                (Place::Alveolar, Manner::Stop, true) => "d",
+                (Place::Alveolar, Manner::Affricate, false) => "ts",
+                (Place::Alveolar, Manner::Affricate, true) => "dz",
+                (Place::Alveolar, Manner::Fricative, false) => "s",
+                (Place::Alveolar, Manner::Fricative, true) => "z",
+                (Place::Alveolar, Manner::Nasal, _) => "n",
+                (Place::Alveolar, Manner::Lateral, _) => "l",
+                (Place::Alveolar, Manner::Rhotic, _) => "r",
+                (Place::Alveolar, Manner::Approximant, _) => "ɹ",
+                (Place::PostAlveolar, Manner::Stop, false) => "ʈ",
+                (Place::PostAlveolar, Manner::Stop, true) => "ɖ",
+                (Place::PostAlveolar, Manner::Affricate, false) => "tʃ",
+                (Place::PostAlveolar, Manner::Affricate, true) => "dʒ",
+                (Place::PostAlveolar, Manner::Fricative, false) => "ʃ",
+                (Place::PostAlveolar, Manner::Fricative, true) => "ʒ",
+                (Place::PostAlveolar, Manner::Nasal, _) => "ɳ",
+                (Place::PostAlveolar, Manner::Lateral, _) => "ɭ",
+                (Place::PostAlveolar, Manner::Rhotic, _) => "ɽ",
+                (Place::PostAlveolar, Manner::Approximant, _) => "ɻ",
+                (Place::Palatal, Manner::Stop, false) => "c",
+                (Place::Palatal, Manner::Stop, true) => "ɟ",
+                (Place::Palatal, Manner::Affricate, false) => "tɕ",
+                (Place::Palatal, Manner::Affricate, true) => "dʑ",
+                (Place::Palatal, Manner::Fricative, false) => "ç",
+                (Place::Palatal, Manner::Fricative, true) => "ʝ",
+                (Place::Palatal, Manner::Nasal, _) => "ɲ",
+                (Place::Palatal, Manner::Lateral, _) => "ʎ",
+                (Place::Palatal, _, _) => "j",
+                (Place::Velar, Manner::Stop, false) => "k",
+                (Place::Velar, Manner::Stop, true) => "ɡ",
+                (Place::Velar, Manner::Affricate, false) => "kx",
+                (Place::Velar, Manner::Affricate, true) => "ɡɣ",
+                (Place::Velar, Manner::Fricative, false) => "x",
+                (Place::Velar, Manner::Fricative, true) => "ɣ",
+                (Place::Velar, Manner::Nasal, _) => "ŋ",
+                (Place::Velar, Manner::Lateral, _) => "ɫ",
+                (Place::Velar, _, _) => "ɰ",
+                (Place::Uvular, Manner::Stop, false) => "q",
+                (Place::Uvular, Manner::Stop, true) => "ɢ",
+                (Place::Uvular, Manner::Affricate, false) => "qχ",
+                (Place::Uvular, Manner::Affricate, true) => "ɢʁ",
+                (Place::Uvular, Manner::Fricative, false) => "χ",
+                (Place::Uvular, Manner::Fricative, true) => "ʁ",
+                (Place::Uvular, Manner::Nasal, _) => "ɴ",
+                (Place::Uvular, _, _) => "ʀ",
+                (Place::Glottal, Manner::Fricative, false) => "h",
+                (Place::Glottal, Manner::Fricative, true) => "ɦ",
+                (Place::Glottal, _, _) => "ʔ",
+            },
+            Features::Vowel {
+                height,
+                backness,
+                rounded,
+                ..
+            } => match (height, backness, rounded) {
+                (Height::High, Backness::Front, false) => "i",
+                (Height::High, Backness::Front, true) => "y",
+                (Height::High, Backness::Central, false) => "ɨ",
+                (Height::High, Backness::Central, true) => "ʉ",
+                (Height::High, Backness::Back, false) => "ɯ",
+                (Height::High, Backness::Back, true) => "u",
+                (Height::Mid, Backness::Front, false) => "e",
+                (Height::Mid, Backness::Front, true) => "ø",
+                (Height::Mid, Backness::Central, false) => "ə",
+                (Height::Mid, Backness::Central, true) => "ɵ",
+                (Height::Mid, Backness::Back, false) => "ɤ",
+                (Height::Mid, Backness::Back, true) => "o",
+                (Height::Low, Backness::Front, false) => "æ",
+                (Height::Low, Backness::Front, true) => "ɶ",
+                (Height::Low, Backness::Central, false) => "a",
+                (Height::Low, Backness::Central, true) => "ɐ",
+                (Height::Low, Backness::Back, false) => "ɑ",
+                (Height::Low, Backness::Back, true) => "ɒ",
+            },
+        }
+    }
+
+    /// Where this phoneme sits on the sonority hierarchy, from voiceless stops (1) up to low
+    /// vowels (11).
+    #[must_use]
+    pub fn sonority(&self) -> u8 {
+        match *self {
+            Features::Consonant { manner, voiced, .. } => {
+                let base = match manner {
+                    Manner::Stop => 1,
+                    Manner::Affricate => 2,
+                    Manner::Fricative => 3,
+                    Manner::Nasal => return 5,
+                    Manner::Lateral => return 6,
+                    Manner::Rhotic => return 7,
+                    Manner::Approximant => return 8,
+                };
+                base + u8::from(voiced)
+            }
+            Features::Vowel { height, .. } => match height {
+                Height::High => 9,
+                Height::Mid => 10,
+                Height::Low => 11,
+            },
+        }
+    }
+
+    #[must_use]
+    pub fn has(&self, feature: Feature) -> bool {
+        match (*self, feature) {
+            (Features::Consonant { .. }, Feature::Consonant)
+            | (Features::Vowel { .. }, Feature::Vowel)
+            | (Features::Vowel { .. }, Feature::Sonorant) => true,
+            (Features::Consonant { manner, .. }, Feature::Sonorant) => matches!(
+                manner,
+                Manner::Nasal | Manner::Lateral | Manner::Rhotic | Manner::Approximant
+            ),
+            (Features::Consonant { manner, .. }, Feature::Obstruent) => {
+                matches!(manner, Manner::Stop | Manner::Affricate | Manner::Fricative)
+            }
+            (Features::Consonant { place, .. }, Feature::Place(p)) => place == p,
+            (Features::Consonant { manner, .. }, Feature::Manner(m)) => manner == m,
+            (Features::Consonant { voiced, .. }, Feature::Voiced) => voiced,
+            (Features::Vowel { .. }, Feature::Voiced) => true,
+            (Features::Vowel { height, .. }, Feature::Height(h)) => height == h,
+            (Features::Vowel { backness, .. }, Feature::Backness(b)) => backness == b,
+            (Features::Vowel { rounded, .. }, Feature::Rounded) => rounded,
+            (Features::Vowel { diphthong, .. }, Feature::Diphthong) => diphthong,
+            (_, _) => false,
+        }
+    }
+
+    /// Returns a copy with `feature` set (or, for binary features, cleared), or `None` if the
+    /// feature does not apply to this kind of phoneme.
+    #[must_use]
+    pub fn with(self, feature: Feature, value: bool) -> Option<Features> {
+        match (self, feature) {
+            (Features::Consonant { manner, voiced, .. }, Feature::Place(place)) if value => {
+                Some(consonant(place, manner, voiced))
+            }
+            (Features::Consonant { place, voiced, .. }, Feature::Manner(manner)) if value => {
+                Some(consonant(place, manner, voiced))
+            }
+            (Features::Consonant { place, manner, .. }, Feature::Voiced) => {
+                Some(consonant(place, manner, value))
+            }
+            (
+                Features::Vowel {
+                    backness,
+                    rounded,
+                    diphthong,
+                    ..
+                },
+                Feature::Height(height),
+            ) if value => Some(Features::Vowel {
+                height,
+                backness,
+                rounded,
+                diphthong,
+            }),
+            (
+                Features::Vowel {
+                    height,
+                    rounded,
+                    diphthong,
+                    ..
+                },
+                Feature::Backness(backness),
+            ) if value => Some(Features::Vowel {
+                height,
+                backness,
+                rounded,
+                diphthong,
+            }),
+            (
+                Features::Vowel {
+                    height,
+                    backness,
+                    diphthong,
+                    ..
+                },
+                Feature::Rounded,
+            ) => Some(Features::Vowel {
+                height,
+                backness,
+                rounded: value,
+                diphthong,
+            }),
+            (_, _) => None,
+        }
+    }
+}
+
+impl Feature {
+    /// Whether this feature can be written as `[-feature]`.
+    #[must_use]
+    pub fn is_binary(self) -> bool {
+        matches!(
+            self,
+            Feature::Voiced | Feature::Rounded | Feature::Diphthong
+        )
+    }
+
+    /// Whether this feature can be changed by a sound change (`[stop] > [fricative]`).
+    #[must_use]
+    pub fn is_settable(self) -> bool {
+        !matches!(
+            self,
+            Feature::Consonant
+                | Feature::Vowel
+                | Feature::Sonorant
+                | Feature::Obstruent
+                | Feature::Diphthong
+        )
+    }
+}
+
+impl FromStr for Feature {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let feature = match s {
+            "consonant" => Feature::Consonant,
+            "vowel" => Feature::Vowel,
+            "sonorant" => Feature::Sonorant,
+            "obstruent" => Feature::Obstruent,
+            "labial" => Feature::Place(Place::Labial),
+            "dental" => Feature::Place(Place::Dental),
+            "alveolar" => Feature::Place(Place::Alveolar),
+            "postalveolar" => Feature::Place(Place::PostAlveolar),
+            "palatal" => Feature::Place(Place::Palatal),
+            "velar" => Feature::Place(Place::Velar),
+            "uvular" => Feature::Place(Place::Uvular),
+            "glottal" => Feature::Place(Place::Glottal),
+            "stop" => Feature::Manner(Manner::Stop),
+            "affricate" => Feature::Manner(Manner::Affricate),
+            "fricative" => Feature::Manner(Manner::Fricative),
+            "nasal" => Feature::Manner(Manner::Nasal),
+            "lateral" => Feature::Manner(Manner::Lateral),
+            "rhotic" => Feature::Manner(Manner::Rhotic),
+            "approximant" => Feature::Manner(Manner::Approximant),
+            "voiced" => Feature::Voiced,
+            "high" => Feature::Height(Height::High),
+            "mid" => Feature::Height(Height::Mid),
+            "low" => Feature::Height(Height::Low),
+            "front" => Feature::Backness(Backness::Front),
+            "central" => Feature::Backness(Backness::Central),
+            "back" => Feature::Backness(Backness::Back),
+            "rounded" => Feature::Rounded,
+            "diphthong" => Feature::Diphthong,
+            _ => return Err(()),
+        };
+
+        Ok(feature)
+    }
+}
+
+impl fmt::Display for Feature {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            Feature::Consonant => "consonant",
+            Feature::Vowel => "vowel",
+            Feature::Sonorant => "sonorant",
+            Feature::Obstruent => "obstruent",
+            Feature::Place(Place::Labial) => "labial",
+            Feature::Place(Place::Dental) => "dental",
+            Feature::Place(Place::Alveolar) => "alveolar",
+            Feature::Place(Place::PostAlveolar) => "postalveolar",
+            Feature::Place(Place::Palatal) => "palatal",
+            Feature::Place(Place::Velar) => "velar",
+            Feature::Place(Place::Uvular) => "uvular",
+            Feature::Place(Place::Glottal) => "glottal",
+            Feature::Manner(Manner::Stop) => "stop",
+            Feature::Manner(Manner::Affricate) => "affricate",
+            Feature::Manner(Manner::Fricative) => "fricative",
+            Feature::Manner(Manner::Nasal) => "nasal",
+            Feature::Manner(Manner::Lateral) => "lateral",
+            Feature::Manner(Manner::Rhotic) => "rhotic",
+            Feature::Manner(Manner::Approximant) => "approximant",
+            Feature::Voiced => "voiced",
+            Feature::Height(Height::High) => "high",
+            Feature::Height(Height::Mid) => "mid",
+            Feature::Height(Height::Low) => "low",
+            Feature::Backness(Backness::Front) => "front",
+            Feature::Backness(Backness::Central) => "central",
+            Feature::Backness(Backness::Back) => "back",
+            Feature::Rounded => "rounded",
+            Feature::Diphthong => "diphthong",
+        };
+
+        write!(f, "{}", name)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn diphthongs() {
+        assert_eq!(
+            features("ou"),
+            Some(Features::Vowel {
+                height: Height::Mid,
+                backness: Backness::Back,
+                rounded: true,
+                diphthong: true,
+            })
+        );
+        assert_eq!(features("ox"), None);
+    }
+
+    #[test]
+    fn distances() {
+        let p = features("p").unwrap();
+
+        assert_eq!(distance(p, p), 0);
+        assert!(distance(p, features("b").unwrap()) < distance(p, features("s").unwrap()));
+        assert!(distance(p, features("s").unwrap()) < distance(p, features("a").unwrap()));
+    }
+
+    #[test]
+    fn sonority() {
+        let sonority = |p| features(p).unwrap().sonority();
+
+        assert!(sonority("t") < sonority("d"));
+        assert!(sonority("d") < sonority("s"));
+        assert!(sonority("s") < sonority("n"));
+        assert!(sonority("n") < sonority("l"));
+        assert!(sonority("l") < sonority("i"));
+        assert!(sonority("i") < sonority("a"));
+    }
+
+    #[test]
+    fn transcriptions() {
+        assert_eq!(ipa("sh"), Some("ʃ".to_string()));
+        assert_eq!(ipa("ng"), Some("ŋ".to_string()));
+        assert_eq!(ipa("y"), Some("j".to_string()));
+        assert_eq!(ipa("ai"), Some("ai".to_string()));
+        assert_eq!(ipa("aa"), Some("aː".to_string()));
+        assert_eq!(ipa("ox"), None);
+    }
+
+    #[test]
+    fn find_prefers_inventory() {
+        let fricative = consonant(Place::Velar, Manner::Fricative, false);
+
+        assert_eq!(find(fricative, &[]), Some("x".to_string()));
+        assert_eq!(find(fricative, &["ĥ".to_string()]), Some("ĥ".to_string()));
+    }
+}
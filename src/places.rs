@@ -0,0 +1,304 @@
+//! Place names built from geographic features, descriptors and per-language generic suffixes.
+
+use crate::{Lexicon, SynthLang, UniquenessTracker, Word};
+use rand::prelude::*;
+use rand_pcg::Pcg64;
+use std::collections::HashMap;
+
+// How many times a blocked or taken name is regenerated from its words before it's given a new
+// word. After twice as many attempts, a taken name will do.
+const ATTEMPTS: usize = 20;
+
+// Descriptors used when a name is asked for without any
+const DESCRIPTORS: &[&str] = &[
+    "red", "black", "white", "green", "gray", "gold", "silver", "big", "small", "long", "old",
+    "new", "cold", "holy", "wolf", "bear", "raven", "eagle", "horse", "stone", "tree", "sun",
+    "moon", "north", "south", "east", "west", "dawn", "shadow", "storm",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FeatureType {
+    River,
+    Lake,
+    Hill,
+    Mountain,
+    Valley,
+    Forest,
+    Ford,
+    Island,
+    Town,
+    Castle,
+}
+
+impl FeatureType {
+    /// The lexicon meaning for this kind of place.
+    #[must_use]
+    pub fn meaning(self) -> &'static str {
+        match self {
+            FeatureType::River => "river",
+            FeatureType::Lake => "lake",
+            FeatureType::Hill => "hill",
+            FeatureType::Mountain => "mountain",
+            FeatureType::Valley => "valley",
+            FeatureType::Forest => "forest",
+            FeatureType::Ford => "ford",
+            FeatureType::Island => "island",
+            FeatureType::Town => "village",
+            FeatureType::Castle => "castle",
+        }
+    }
+
+    fn is_settlement(self) -> bool {
+        matches!(self, FeatureType::Town | FeatureType::Castle)
+    }
+}
+
+/// Something a place is named after.
+#[derive(Debug, Clone)]
+pub enum Descriptor {
+    /// A lexicon meaning, such as a color (`"green"`) or size (`"big"`).
+    Meaning(String),
+    /// The person who founded or owns the place.
+    Founder(Word),
+}
+
+impl Descriptor {
+    fn key(&self) -> String {
+        match self {
+            Descriptor::Meaning(meaning) => meaning.clone(),
+            Descriptor::Founder(name) => format!("@{}", name),
+        }
+    }
+}
+
+/// Generates names for places on a map.
+///
+/// The same feature and descriptors always give the same name from a generator, and a different
+/// name from any other place it has named, as far as its uniqueness tracker can manage.
+/// Settlements, and some natural features, use a worn-down generic suffix instead of the full
+/// word for the feature, the way "town" became "-ton".
+#[derive(Debug, Clone)]
+pub struct PlaceNameGenerator<'a> {
+    lang: &'a SynthLang,
+    lexicon: Lexicon,
+    generic_suffixes: bool,
+    uniqueness: Option<UniquenessTracker>,
+    names: HashMap<(String, bool), Word>,
+    rng: Pcg64,
+}
+
+impl<'a> PlaceNameGenerator<'a> {
+    #[must_use]
+    pub fn new(lang: &'a SynthLang, seed: u64) -> Self {
+        Self::with_lexicon(lang, Lexicon::new(), seed)
+    }
+
+    /// A generator that takes its words from `lexicon`, adding any that are missing.
+    #[must_use]
+    pub fn with_lexicon(lang: &'a SynthLang, lexicon: Lexicon, seed: u64) -> Self {
+        Self {
+            lang,
+            lexicon,
+            generic_suffixes: true,
+            uniqueness: lang.uniqueness_tracker().cloned(),
+            names: HashMap::new(),
+            rng: Pcg64::seed_from_u64(seed),
+        }
+    }
+
+    /// Turns generic suffixes on or off. They're on by default.
+    pub fn set_generic_suffixes(&mut self, generic_suffixes: bool) {
+        self.generic_suffixes = generic_suffixes;
+    }
+
+    /// Sets the tracker that names are checked against and added to. It starts out as a copy
+    /// of the language's own tracker, if it has one.
+    pub fn set_uniqueness_tracker(&mut self, tracker: Option<UniquenessTracker>) {
+        self.uniqueness = tracker;
+    }
+
+    #[must_use]
+    pub fn uniqueness_tracker(&self) -> Option<&UniquenessTracker> {
+        self.uniqueness.as_ref()
+    }
+
+    #[must_use]
+    pub fn lexicon(&self) -> &Lexicon {
+        &self.lexicon
+    }
+
+    /// The generic suffix for a kind of place: the first syllable of its word.
+    pub fn generic_suffix(&mut self, feature: FeatureType) -> Word {
+        let word = self.lexicon.word(self.lang, feature.meaning());
+
+        Word {
+            parts: word.parts.iter().take(1).cloned().collect(),
+            compound_rule: word.compound_rule,
+        }
+    }
+
+    /// Names a place. With no descriptors, one is picked at random.
+    pub fn name(&mut self, feature: FeatureType, descriptors: &[Descriptor]) -> Word {
+        let random;
+        let descriptors = if descriptors.is_empty() {
+            random = [Descriptor::Meaning(
+                DESCRIPTORS.choose(&mut self.rng).unwrap().to_string(),
+            )];
+            &random[..]
+        } else {
+            descriptors
+        };
+
+        let key: Vec<String> = descriptors.iter().map(Descriptor::key).collect();
+        let key = format!("\0place:{}:{}", feature.meaning(), key.join(":"));
+        if let Some(name) = self.names.get(&(key.clone(), self.generic_suffixes)) {
+            return name.clone();
+        }
+
+        // Blocked and taken names are regenerated by salting the key, which keeps them
+        // deterministic
+        let lang = self.lang;
+        let tracker = self.uniqueness.take();
+        let name = lang.generate_checked(tracker.as_ref(), ATTEMPTS * 2, |n| {
+            let salted = if n == 0 {
+                key.clone()
+            } else {
+                format!("{}#{}", key, n)
+            };
+            let mut rng = lang.keyed_rng(SynthLang::hash_key(&salted));
+
+            if n <= ATTEMPTS {
+                // The words themselves may be the problem, so later attempts add a linking
+                // syllable
+                self.generate(&mut rng, feature, descriptors, n > ATTEMPTS / 2)
+            } else {
+                // If even that doesn't help, the place gets a name of its own
+                lang.generate_word(&mut rng)
+            }
+        });
+        self.uniqueness = tracker;
+
+        if let Some(tracker) = &mut self.uniqueness {
+            tracker.insert(&name);
+        }
+        self.names
+            .insert((key, self.generic_suffixes), name.clone());
+
+        name
+    }
+
+    fn generate(
+        &mut self,
+        rng: &mut Pcg64,
+        feature: FeatureType,
+        descriptors: &[Descriptor],
+        link: bool,
+    ) -> Word {
+        let founded = descriptors
+            .iter()
+            .any(|d| matches!(d, Descriptor::Founder(_)));
+        let head =
+            if self.generic_suffixes && (feature.is_settlement() || founded || rng.gen_bool(0.3)) {
+                self.generic_suffix(feature)
+            } else {
+                self.lexicon.word(self.lang, feature.meaning()).clone()
+            };
+
+        let mut words: Vec<Word> = descriptors
+            .iter()
+            .map(|d| match d {
+                Descriptor::Meaning(meaning) => self.lexicon.word(self.lang, meaning).clone(),
+                Descriptor::Founder(name) => name.clone(),
+            })
+            .collect();
+        if link {
+            words.push(Word {
+                parts: vec![self
+                    .lang
+                    .syllable(rng, words.last().and_then(|w| w.parts.last()))],
+                compound_rule: head.compound_rule,
+            });
+        }
+        words.push(head);
+
+        let mut words = words.into_iter();
+        let first = words.next().unwrap();
+        words.fold(first, |left, right| {
+            self.lang.generate_compound(rng, &left, &right)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn place_names() {
+        let lang = SynthLang::new(6);
+        let mut places = PlaceNameGenerator::new(&lang, 0);
+
+        let green = [Descriptor::Meaning("green".to_string())];
+        let name = places.name(FeatureType::River, &green);
+        assert_eq!(
+            name.to_string(),
+            PlaceNameGenerator::new(&lang, 1)
+                .name(FeatureType::River, &green)
+                .to_string()
+        );
+
+        let founder = lang.word_for("aldric");
+        let town = places.name(FeatureType::Town, &[Descriptor::Founder(founder)]);
+        let suffix = places.generic_suffix(FeatureType::Town);
+        assert!(town.parts.len() <= lang.word_for("aldric").parts.len() + suffix.parts.len());
+
+        places.set_generic_suffixes(false);
+        let hill = places.name(FeatureType::Hill, &[]);
+        assert!(hill.parts.len() >= 2);
+    }
+
+    #[test]
+    fn unique() {
+        let lang = SynthLang::new(6);
+        let green = [Descriptor::Meaning("green".to_string())];
+        let name = PlaceNameGenerator::new(&lang, 0).name(FeatureType::River, &green);
+
+        let mut places = PlaceNameGenerator::new(&lang, 0);
+        let mut tracker = UniquenessTracker::new(crate::Similarity::Spelling, 0);
+        tracker.insert(&name);
+        places.set_uniqueness_tracker(Some(tracker));
+        let other = places.name(FeatureType::River, &green);
+        assert_ne!(other.to_string(), name.to_string());
+        assert_eq!(
+            places.name(FeatureType::River, &green).to_string(),
+            other.to_string()
+        );
+
+        let mut names: Vec<String> = DESCRIPTORS
+            .iter()
+            .map(|d| {
+                let descriptor = [Descriptor::Meaning(d.to_string())];
+                places.name(FeatureType::Town, &descriptor).to_string()
+            })
+            .collect();
+        names.push(other.to_string());
+        let count = names.len();
+        names.sort();
+        names.dedup();
+        assert_eq!(names.len(), count);
+    }
+
+    #[test]
+    fn filtered() {
+        let mut lang = SynthLang::new(6);
+        let red = [Descriptor::Meaning("red".to_string())];
+        let unfiltered = PlaceNameGenerator::new(&lang, 0).name(FeatureType::Lake, &red);
+
+        let mut blocklist = crate::Blocklist::empty();
+        blocklist.add_word(&unfiltered.to_string());
+        lang.set_content_filter(Some(std::sync::Arc::new(blocklist)));
+
+        let filtered = PlaceNameGenerator::new(&lang, 0).name(FeatureType::Lake, &red);
+        assert!(!lang.is_blocked(&filtered));
+    }
+}
@@ -0,0 +1,172 @@
+//! Scoring how easy a word is to read aloud, and rejecting words that score too low.
+
+use crate::phoneme;
+use crate::{SyllablePartType, SynthLang, Word};
+
+impl Word {
+    /// A score between 0 and 1 for how easy this word is to pronounce. It goes down for long
+    /// consonant clusters and vowel runs, sonority that rises into a coda or falls into an
+    /// onset, too few or too many vowels, great length and unusual letters.
+    #[must_use]
+    pub fn pronounceability(&self) -> f32 {
+        // (syllable index, index within the syllable, part) for every phoneme in the word
+        let parts: Vec<_> = self
+            .parts
+            .iter()
+            .enumerate()
+            .flat_map(|(i, s)| s.parts.iter().enumerate().map(move |(j, p)| (i, j, p)))
+            .collect();
+        if parts.is_empty() {
+            return 0.0;
+        }
+
+        let mut score = 1.0_f32;
+
+        // Clusters
+        let mut run = (SyllablePartType::Vowel, 0);
+        for (_, _, part) in &parts {
+            if part.part_type == run.0 {
+                run.1 += 1;
+            } else {
+                run = (part.part_type, 1);
+            }
+
+            score *= match (&run.0, run.1) {
+                (_, 1) => 1.0,
+                (SyllablePartType::Consonant, 2) => 0.9,
+                (SyllablePartType::Consonant, 3) => 0.65,
+                (SyllablePartType::Vowel, 2) => 0.85,
+                (_, _) => 0.5,
+            };
+        }
+
+        // Sonority sequencing between neighbouring consonants
+        for pair in parts.windows(2) {
+            let ((sa, _, a), (sb, jb, b)) = (pair[0], pair[1]);
+            if a.part_type != SyllablePartType::Consonant
+                || b.part_type != SyllablePartType::Consonant
+            {
+                continue;
+            }
+
+            let (fa, fb) = match (phoneme::features(&a.value), phoneme::features(&b.value)) {
+                (Some(fa), Some(fb)) => (fa.sonority(), fb.sonority()),
+                _ => continue,
+            };
+
+            let ok = if sa == sb {
+                // Within a syllable, sonority rises towards the vowel and falls after it
+                let before_vowel = self.parts[sb].parts[jb..]
+                    .iter()
+                    .any(|p| p.part_type == SyllablePartType::Vowel);
+                if before_vowel {
+                    fa < fb
+                } else {
+                    fa > fb
+                }
+            } else {
+                // Syllable contact: a coda should be at least as sonorous as the next onset
+                fa >= fb
+            };
+
+            if !ok {
+                score *= 1.0 - 0.04 * f32::from(fa.abs_diff(fb).max(1));
+            }
+        }
+
+        // Vowel ratio
+        let vowels = parts
+            .iter()
+            .filter(|(_, _, p)| p.part_type == SyllablePartType::Vowel)
+            .count();
+        let ratio = vowels as f32 / parts.len() as f32;
+        let off = (0.3 - ratio).max(ratio - 0.65).max(0.0);
+        score *= (1.0 - 1.5 * off).max(0.3);
+
+        // Length
+        let spelled = self.to_string();
+        let letters = spelled.chars().count();
+        score *= 0.85_f32.powi(self.parts.len().saturating_sub(3) as i32);
+        score *= 0.95_f32.powi(letters.saturating_sub(12) as i32);
+
+        // Unusual letters
+        let exotic = spelled.chars().filter(|c| !c.is_ascii()).count();
+        score *= 1.0 - 0.3 * exotic as f32 / letters.max(1) as f32;
+
+        score.clamp(0.0, 1.0)
+    }
+}
+
+impl SynthLang {
+    /// Makes `word()` and `compound()` regenerate words whose pronounceability is below
+    /// `threshold`, or turns that off with `None`.
+    pub fn set_min_pronounceability(&mut self, threshold: Option<f32>) {
+        self.min_pronounceability = threshold;
+    }
+
+    #[must_use]
+    pub fn min_pronounceability(&self) -> Option<f32> {
+        self.min_pronounceability
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{CompoundRule, Syllable, SyllablePart};
+
+    fn word(syllables: &[&[&str]]) -> Word {
+        let parts = syllables
+            .iter()
+            .map(|s| Syllable {
+                parts: s
+                    .iter()
+                    .map(|p| SyllablePart {
+                        part_type: if phoneme::features(p).is_some_and(|f| f.is_vowel()) {
+                            SyllablePartType::Vowel
+                        } else {
+                            SyllablePartType::Consonant
+                        },
+                        value: p.to_string(),
+                    })
+                    .collect(),
+            })
+            .collect();
+
+        Word {
+            parts,
+            compound_rule: CompoundRule::DropNone,
+        }
+    }
+
+    #[test]
+    fn scores() {
+        let easy = word(&[&["t", "a"], &["n", "a"]]);
+        let contact = word(&[&["a", "t"], &["n", "a"]]);
+        let cluster = word(&[&["t", "a", "k"], &["s", "t"], &["r", "a"]]);
+        let long = word(&[
+            &["t", "a"],
+            &["n", "a"],
+            &["l", "o"],
+            &["m", "i"],
+            &["r", "u"],
+        ]);
+        let exotic = word(&[&["ŕ", "ŏ"], &["ẅ", "ű"]]);
+
+        assert!(easy.pronounceability() > 0.95);
+        assert!(contact.pronounceability() < easy.pronounceability());
+        assert!(cluster.pronounceability() < contact.pronounceability());
+        assert!(long.pronounceability() < easy.pronounceability());
+        assert!(exotic.pronounceability() < easy.pronounceability());
+    }
+
+    #[test]
+    fn threshold() {
+        let mut lang = SynthLang::new(21);
+        lang.set_min_pronounceability(Some(0.75));
+
+        for _ in 0..100 {
+            assert!(lang.word().pronounceability() >= 0.75);
+        }
+    }
+}
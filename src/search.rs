@@ -0,0 +1,195 @@
+//! Searching through seeds for languages with the properties a designer is after.
+
+use crate::{SyllableType, SynthLang};
+use std::fmt;
+use std::num::NonZeroUsize;
+use std::ops::{Range, RangeInclusive};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, Receiver};
+use std::sync::Arc;
+use std::thread;
+
+type Criterion = Box<dyn Fn(&SynthLang) -> bool + Send + Sync>;
+
+/// Checks a range of seeds for languages that meet every criterion, spread over several
+/// threads.
+pub struct SeedSearch {
+    seeds: Range<u64>,
+    threads: usize,
+    criteria: Vec<Criterion>,
+}
+
+impl fmt::Debug for SeedSearch {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("SeedSearch")
+            .field("seeds", &self.seeds)
+            .field("threads", &self.threads)
+            .field("criteria", &self.criteria.len())
+            .finish()
+    }
+}
+
+impl SeedSearch {
+    /// A search through `seeds` with one thread per CPU and no criteria yet.
+    #[must_use]
+    pub fn new(seeds: Range<u64>) -> Self {
+        Self {
+            seeds,
+            threads: thread::available_parallelism().map_or(1, NonZeroUsize::get),
+            criteria: vec![],
+        }
+    }
+
+    pub fn set_threads(&mut self, threads: usize) {
+        self.threads = threads.max(1);
+    }
+
+    /// Only accepts languages for which `criterion` is true.
+    pub fn require<F>(&mut self, criterion: F)
+    where
+        F: Fn(&SynthLang) -> bool + Send + Sync + 'static,
+    {
+        self.criteria.push(Box::new(criterion));
+    }
+
+    /// Only accepts languages with all of `phonemes`.
+    pub fn require_phonemes(&mut self, phonemes: &[&str]) {
+        let phonemes: Vec<String> = phonemes.iter().map(ToString::to_string).collect();
+        self.require(move |lang| phonemes.iter().all(|p| lang.has_phoneme(p)));
+    }
+
+    /// Only accepts languages with none of `phonemes`.
+    pub fn forbid_phonemes(&mut self, phonemes: &[&str]) {
+        let phonemes: Vec<String> = phonemes.iter().map(ToString::to_string).collect();
+        self.require(move |lang| !phonemes.iter().any(|p| lang.has_phoneme(p)));
+    }
+
+    /// Only accepts languages that pick `syllable_type` for a share of their syllables within
+    /// `share`, e.g. `0.0..=0.0` for languages without that shape at all.
+    pub fn require_syllable_share(
+        &mut self,
+        syllable_type: SyllableType,
+        share: RangeInclusive<f64>,
+    ) {
+        self.require(move |lang| {
+            let weight = match syllable_type {
+                SyllableType::CV => lang.cv_weight,
+                SyllableType::VC => lang.vc_weight,
+                SyllableType::CVC => lang.cvc_weight,
+            };
+            let total = lang.cv_weight + lang.vc_weight + lang.cvc_weight;
+
+            share.contains(&(f64::from(weight) / f64::from(total.max(1))))
+        });
+    }
+
+    /// Only accepts languages whose words are this many letters long on average.
+    pub fn require_word_length(&mut self, letters: RangeInclusive<f64>) {
+        self.require(move |lang| letters.contains(&lang.statistics().expected_letters));
+    }
+
+    /// Only accepts languages whose first `count` words from `word()` all satisfy `predicate`,
+    /// e.g. a regex match.
+    pub fn require_words<F>(&mut self, count: usize, predicate: F)
+    where
+        F: Fn(&str) -> bool + Send + Sync + 'static,
+    {
+        self.require(move |lang| {
+            let mut lang = lang.clone();
+            (0..count).all(|_| predicate(&lang.word().to_string()))
+        });
+    }
+
+    /// Only accepts languages within `max_distance` of `target`. See `SynthLang::distance`.
+    pub fn require_similar_to(&mut self, target: SynthLang, max_distance: f64) {
+        self.require(move |lang| lang.distance(&target) <= max_distance);
+    }
+
+    /// Starts searching. Matching seeds are streamed as they're found, so they don't
+    /// necessarily come in order. Dropping the results stops the search.
+    #[must_use]
+    pub fn run(self) -> SearchResults {
+        let (sender, receiver) = mpsc::channel();
+        let stopped = Arc::new(AtomicBool::new(false));
+        let criteria = Arc::new(self.criteria);
+        let threads = self.threads as u64;
+
+        for offset in 0..threads {
+            let sender = sender.clone();
+            let criteria = Arc::clone(&criteria);
+            let stopped = Arc::clone(&stopped);
+            let mut seeds = self.seeds.clone();
+            let first = seeds.start.saturating_add(offset);
+
+            thread::spawn(move || {
+                seeds.start = first;
+                for seed in seeds.step_by(threads as usize) {
+                    if stopped.load(Ordering::Relaxed) {
+                        return;
+                    }
+
+                    let lang = SynthLang::new(seed);
+                    if criteria.iter().all(|c| c(&lang)) && sender.send(seed).is_err() {
+                        return;
+                    }
+                }
+            });
+        }
+
+        SearchResults { receiver, stopped }
+    }
+}
+
+/// Seeds found by a `SeedSearch`, in the order they're found.
+#[derive(Debug)]
+pub struct SearchResults {
+    receiver: Receiver<u64>,
+    stopped: Arc<AtomicBool>,
+}
+
+impl Iterator for SearchResults {
+    type Item = u64;
+
+    fn next(&mut self) -> Option<u64> {
+        self.receiver.recv().ok()
+    }
+}
+
+impl Drop for SearchResults {
+    fn drop(&mut self) {
+        self.stopped.store(true, Ordering::Relaxed);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn search() {
+        let mut search = SeedSearch::new(0..300);
+        search.set_threads(4);
+        search.require_phonemes(&["sh"]);
+        search.require_syllable_share(SyllableType::VC, 0.0..=0.0);
+
+        let mut found: Vec<u64> = search.run().collect();
+        found.sort_unstable();
+
+        let expected: Vec<u64> = (0..300)
+            .filter(|seed| {
+                let lang = SynthLang::new(*seed);
+                lang.consonants.iter().any(|c| c == "sh") && lang.vc_weight == 0
+            })
+            .collect();
+        assert!(!expected.is_empty());
+        assert_eq!(found, expected);
+    }
+
+    #[test]
+    fn stops_early() {
+        let mut search = SeedSearch::new(0..u64::MAX);
+        search.require_words(5, |w| w.chars().all(|c| c.is_ascii_lowercase()));
+
+        assert_eq!(search.run().take(3).count(), 3);
+    }
+}
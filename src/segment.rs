@@ -0,0 +1,271 @@
+//! Keeping a word's phonemes canonical, so that its spelling, its syllables and its IPA
+//! transcription always agree with each other, and reading that structure back out.
+
+use crate::phoneme;
+use crate::{CompoundRule, Syllable, SyllablePart, SyllablePartType, SynthLang, Word};
+use std::ops::Range;
+
+impl Word {
+    /// Builds a word, dropping any phoneme that would spell the same letter three times in a
+    /// row. A syllable left without its vowel joins the one before it.
+    pub(crate) fn new(parts: Vec<Syllable>, compound_rule: CompoundRule) -> Word {
+        let mut spelled = String::new();
+        let mut syllables: Vec<Syllable> = vec![];
+
+        for syllable in parts {
+            let had_vowel = syllable.has_vowel();
+
+            let mut kept = Syllable { parts: vec![] };
+            for part in syllable.parts {
+                if repeats_three_times(&spelled, &part.value) {
+                    continue;
+                }
+                spelled.push_str(&part.value);
+                kept.parts.push(part);
+            }
+
+            match syllables.last_mut() {
+                Some(previous) if had_vowel && !kept.has_vowel() => {
+                    previous.parts.extend(kept.parts);
+                }
+                _ if kept.parts.is_empty() => {}
+                _ => syllables.push(kept),
+            }
+        }
+
+        Word {
+            parts: syllables,
+            compound_rule,
+        }
+    }
+
+    #[must_use]
+    pub fn syllables(&self) -> &[Syllable] {
+        &self.parts
+    }
+
+    #[must_use]
+    pub fn compound_rule(&self) -> CompoundRule {
+        self.compound_rule
+    }
+
+    /// Where each syllable starts and ends in the spelled word, as byte offsets.
+    #[must_use]
+    pub fn syllable_boundaries(&self) -> Vec<Range<usize>> {
+        let mut start = 0;
+
+        self.parts
+            .iter()
+            .map(|s| {
+                let len: usize = s.parts.iter().map(|p| p.value.len()).sum();
+                start += len;
+                start - len..start
+            })
+            .collect()
+    }
+
+    /// The spelled word with a hyphen between syllables, as in "ta-nar".
+    #[must_use]
+    pub fn hyphenated(&self) -> String {
+        self.parts
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>()
+            .join("-")
+    }
+
+    /// The spellings of this word's phonemes, in order.
+    pub fn phonemes(&self) -> impl Iterator<Item = &str> {
+        self.parts
+            .iter()
+            .flat_map(|s| s.parts.iter())
+            .map(|p| p.value.as_str())
+    }
+
+    /// A broad IPA transcription, with syllables separated by `.`. Phonemes without known
+    /// features are written as they're spelled.
+    #[must_use]
+    pub fn ipa(&self) -> String {
+        self.parts
+            .iter()
+            .map(|s| {
+                s.parts
+                    .iter()
+                    .map(|p| phoneme::ipa(&p.value).unwrap_or_else(|| p.value.clone()))
+                    .collect::<String>()
+            })
+            .collect::<Vec<_>>()
+            .join(".")
+    }
+}
+
+impl SynthLang {
+    /// Builds a word like `Word::new`, then splits it into syllables the way `parse` would, so
+    /// that parsing its spelling gives the same word back. Dropping a phoneme can leave a
+    /// syllable with a shape or transition the language doesn't have, which the new split
+    /// avoids where it can.
+    pub(crate) fn canonical_word(&self, parts: Vec<Syllable>, compound_rule: CompoundRule) -> Word {
+        let word = Word::new(parts, compound_rule);
+
+        // The split only uses the language's shapes, but may still need a transition it lacks
+        match self.syllabify_text(&word.to_string()) {
+            Some(parts)
+                if parts
+                    .iter()
+                    .all(|s| self.illegal_transitions(s).next().is_none()) =>
+            {
+                Word {
+                    parts,
+                    compound_rule,
+                }
+            }
+            _ => word,
+        }
+    }
+}
+
+impl Syllable {
+    #[must_use]
+    pub fn parts(&self) -> &[SyllablePart] {
+        &self.parts
+    }
+
+    /// Whether spelling this syllable after `spelled` would write the same letter three times
+    /// in a row, so that `Word::new` would drop one of its phonemes.
+    pub(crate) fn repeats_after(&self, spelled: &str) -> bool {
+        repeats_three_times(spelled, &self.to_string())
+    }
+
+    fn has_vowel(&self) -> bool {
+        self.parts
+            .iter()
+            .any(|p| p.part_type == SyllablePartType::Vowel)
+    }
+}
+
+impl SyllablePart {
+    #[must_use]
+    pub fn part_type(&self) -> SyllablePartType {
+        self.part_type
+    }
+
+    #[must_use]
+    pub fn value(&self) -> &str {
+        &self.value
+    }
+}
+
+/// Whether spelling `phoneme` after `spelled` would write the same letter three times in a row.
+fn repeats_three_times(spelled: &str, phoneme: &str) -> bool {
+    let tail = spelled.chars().rev().take(2).collect::<Vec<_>>();
+    let mut run = (None, 0);
+
+    for c in tail.into_iter().rev().chain(phoneme.chars()) {
+        if run.0 == Some(c) {
+            run.1 += 1;
+        } else {
+            run = (Some(c), 1);
+        }
+
+        if run.1 >= 3 {
+            return true;
+        }
+    }
+
+    false
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn word(syllables: &[&[&str]]) -> Word {
+        let parts = syllables
+            .iter()
+            .map(|s| Syllable {
+                parts: s
+                    .iter()
+                    .map(|p| SyllablePart {
+                        part_type: if phoneme::features(p).is_some_and(|f| f.is_vowel()) {
+                            SyllablePartType::Vowel
+                        } else {
+                            SyllablePartType::Consonant
+                        },
+                        value: p.to_string(),
+                    })
+                    .collect(),
+            })
+            .collect();
+
+        Word::new(parts, CompoundRule::DropNone)
+    }
+
+    #[test]
+    fn repeats() {
+        let long = word(&[&["t", "aa"], &["a", "n"]]);
+        assert_eq!(long.to_string(), "taan");
+        assert_eq!(long.parts.len(), 1);
+        assert_eq!(long.ipa(), "taːn");
+
+        let doubled = word(&[&["a", "s"], &["s", "a"]]);
+        assert_eq!(doubled.to_string(), "assa");
+        assert_eq!(doubled.ipa(), "as.sa");
+
+        // The whole diphthong goes rather than just its first letter
+        let diphthong = word(&[&["t", "aa"], &["ai", "n"]]);
+        assert_eq!(diphthong.to_string(), "taan");
+        assert_eq!(diphthong.phonemes().collect::<Vec<_>>(), ["t", "aa", "n"]);
+    }
+
+    #[test]
+    fn consistent() {
+        for seed in 0..20 {
+            let mut lang = crate::SynthLang::new(seed);
+            for _ in 0..50 {
+                let word = lang.word();
+                let spelled = word.to_string();
+                assert_eq!(spelled, word.phonemes().collect::<String>());
+                assert!(!spelled
+                    .chars()
+                    .collect::<Vec<_>>()
+                    .windows(3)
+                    .any(|w| w[0] == w[1] && w[1] == w[2]));
+                assert!(word.parts.iter().all(Syllable::has_vowel));
+            }
+        }
+    }
+
+    #[test]
+    fn parses_back() {
+        for seed in 0..300 {
+            let mut lang = crate::SynthLang::new(seed);
+            for _ in 0..30 {
+                let word = lang.word();
+                assert!(lang.log_probability(&word).is_finite(), "{}", word);
+
+                let parsed = lang.parse(&word.to_string()).unwrap();
+                assert_eq!(parsed.hyphenated(), word.hyphenated());
+                assert!(parsed.phonemes().eq(word.phonemes()));
+            }
+        }
+    }
+
+    #[test]
+    fn structure() {
+        let word = word(&[&["sh", "a"], &["ñ", "a", "r"]]);
+        let spelled = word.to_string();
+
+        let syllables: Vec<&str> = word
+            .syllable_boundaries()
+            .into_iter()
+            .map(|r| &spelled[r])
+            .collect();
+        assert_eq!(syllables, ["sha", "ñar"]);
+        assert_eq!(word.hyphenated(), "sha-ñar");
+
+        let second = &word.syllables()[1];
+        assert_eq!(second.parts()[0].value(), "ñ");
+        assert_eq!(second.parts()[1].part_type(), SyllablePartType::Vowel);
+        assert_eq!(word.compound_rule(), CompoundRule::DropNone);
+    }
+}
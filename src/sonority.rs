@@ -0,0 +1,293 @@
+//! The sonority sequencing principle: onsets rise in sonority towards the vowel, codas fall
+//! away from it, and across a syllable boundary a coda shouldn't be less sonorous than the
+//! onset that follows it (the syllable contact law).
+
+use crate::phoneme;
+use crate::{Syllable, SyllablePartType, SynthLang};
+use rand::prelude::*;
+use rand_pcg::Pcg64;
+
+/// How strictly a language follows the sonority sequencing principle.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Strictness {
+    /// Any sequence is allowed.
+    Off,
+    /// Sonority may rise a little across a syllable boundary, as in "at.la".
+    Lenient,
+    /// Sonority never rises across a syllable boundary.
+    Strict,
+}
+
+impl Strictness {
+    /// How far sonority may rise from one consonant to the next where it should fall.
+    fn max_rise(self) -> Option<u8> {
+        match self {
+            Strictness::Off => None,
+            Strictness::Lenient => Some(2),
+            Strictness::Strict => Some(0),
+        }
+    }
+}
+
+impl SynthLang {
+    /// Sets how strictly generated words follow the sonority sequencing principle. It's off by
+    /// default.
+    pub fn set_sonority_strictness(&mut self, strictness: Strictness) {
+        self.sonority = strictness;
+        self.clear_casing();
+    }
+
+    #[must_use]
+    pub fn sonority_strictness(&self) -> Strictness {
+        self.sonority
+    }
+
+    /// The sonority of every phoneme in the inventory, least sonorous first. Phonemes without
+    /// known features are left out.
+    #[must_use]
+    pub fn sonority_hierarchy(&self) -> Vec<(String, u8)> {
+        let mut hierarchy: Vec<(String, u8)> = self
+            .consonants
+            .iter()
+            .chain(self.vowels.iter())
+            .filter_map(|p| Some((p.clone(), self.sonority_of(p)?)))
+            .collect();
+        hierarchy.sort_by_key(|p| p.1);
+
+        hierarchy
+    }
+
+    /// Whether `first` may be followed by `second` in a cluster. `rising` is true in onsets,
+    /// where sonority should rise, and false in codas and across syllable boundaries, where
+    /// it should fall.
+    #[must_use]
+    pub fn allows_cluster(&self, first: &str, second: &str, rising: bool) -> bool {
+        let max_rise = match self.sonority.max_rise() {
+            Some(max_rise) => max_rise,
+            None => return true,
+        };
+
+        match (self.sonority_of(first), self.sonority_of(second)) {
+            (Some(a), Some(b)) if rising => b > a,
+            (Some(a), Some(b)) => b <= a + max_rise,
+            _ => true,
+        }
+    }
+
+    fn sonority_of(&self, phoneme: &str) -> Option<u8> {
+        phoneme::features(phoneme).map(|f| f.sonority())
+    }
+
+    /// Picks the first consonant of a syllable, keeping to the syllable contact law after a
+    /// syllable that ends in a consonant. If no onset the language uses may follow that
+    /// consonant, any onset will do, so a strict language can still break the law there.
+    pub(crate) fn onset(&self, rng: &mut Pcg64, previous: Option<&Syllable>) -> String {
+        let coda = previous
+            .and_then(|s| s.parts.last())
+            .filter(|p| p.part_type == SyllablePartType::Consonant);
+
+        let coda = match coda {
+            Some(coda) if self.sonority != Strictness::Off => coda,
+            _ => return self.next_part(rng, "\0", SyllablePartType::Consonant),
+        };
+
+        self.contact_onset(rng, &coda.value, None)
+            .unwrap_or_else(|| self.next_part(rng, "\0", SyllablePartType::Consonant))
+    }
+
+    /// Picks an onset that may follow `coda` across a syllable boundary and, if given, may be
+    /// followed by `vowel`.
+    fn contact_onset(&self, rng: &mut Pcg64, coda: &str, vowel: Option<&str>) -> Option<String> {
+        let candidates: Vec<&(String, i32)> = self.next_part_weights["\0"]
+            .1
+            .iter()
+            .filter(|(c, w)| {
+                *w > 0
+                    && self.allows_cluster(coda, c, false)
+                    && vowel.map_or(true, |v| self.weight(c, v) > 0)
+            })
+            .collect();
+
+        candidates
+            .choose_weighted(rng, |c| c.1)
+            .ok()
+            .map(|c| c.0.clone())
+    }
+
+    /// Appends `syllables` to `parts`, such as where two words are compounded. If the first of
+    /// them breaks the syllable contact law, it gets a new onset that also keeps to the
+    /// language's transitions, or keeps its own if there isn't one.
+    pub(crate) fn append_syllables<I>(
+        &self,
+        rng: &mut Pcg64,
+        parts: &mut Vec<Syllable>,
+        syllables: I,
+    ) where
+        I: IntoIterator<Item = Syllable>,
+    {
+        let mut syllables = syllables.into_iter();
+        if let Some(mut first) = syllables.next() {
+            let coda = parts
+                .last()
+                .and_then(|s| s.parts.last())
+                .filter(|p| p.part_type == SyllablePartType::Consonant);
+            let onset = first
+                .parts
+                .first()
+                .filter(|p| p.part_type == SyllablePartType::Consonant);
+
+            if let (Some(coda), Some(onset)) = (coda, onset) {
+                if !self.allows_cluster(&coda.value, &onset.value, false) {
+                    let vowel = first.parts.get(1).map(|p| p.value.as_str());
+                    if let Some(onset) = self.contact_onset(rng, &coda.value, vowel) {
+                        first.parts[0].value = onset;
+                    }
+                }
+            }
+            parts.push(first);
+        }
+
+        parts.extend(syllables);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::SyllablePart;
+
+    #[test]
+    fn syllable_contact() {
+        for seed in 0..20 {
+            let mut lang = SynthLang::new(seed);
+            lang.set_sonority_strictness(Strictness::Strict);
+
+            for _ in 0..50 {
+                let word = lang.word();
+                for pair in word.parts.windows(2) {
+                    let coda = pair[0].parts.last().unwrap();
+                    let onset = pair[1].parts.first().unwrap();
+                    if coda.part_type == SyllablePartType::Consonant
+                        && onset.part_type == SyllablePartType::Consonant
+                    {
+                        assert!(
+                            lang.allows_cluster(&coda.value, &onset.value, false),
+                            "{} {}",
+                            coda.value,
+                            onset.value
+                        );
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn compound_seam() {
+        for seed in 0..20 {
+            let mut lang = SynthLang::new(seed);
+            lang.set_sonority_strictness(Strictness::Strict);
+
+            for _ in 0..50 {
+                let left = lang.word();
+                let right = lang.word();
+                let compound = lang.compound(&left, &right);
+                for syllable in compound.syllables() {
+                    if let [onset, vowel, ..] = &syllable.parts[..] {
+                        if onset.part_type == SyllablePartType::Consonant {
+                            assert!(lang.weight(&onset.value, &vowel.value) > 0, "{}", syllable);
+                        }
+                    }
+                }
+                for pair in compound.parts.windows(2) {
+                    let coda = pair[0].parts.last().unwrap();
+                    let onset = pair[1].parts.first().unwrap();
+                    if coda.part_type == SyllablePartType::Consonant
+                        && onset.part_type == SyllablePartType::Consonant
+                    {
+                        assert!(
+                            lang.allows_cluster(&coda.value, &onset.value, false),
+                            "{} {}",
+                            coda.value,
+                            onset.value
+                        );
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn onset_fallback() {
+        let mut lang = SynthLang::new(1);
+        lang.set_sonority_strictness(Strictness::Strict);
+        lang.add_phoneme("t", SyllablePartType::Consonant);
+        lang.add_phoneme("r", SyllablePartType::Consonant);
+        for consonant in lang.consonants.clone() {
+            if consonant != "r" {
+                lang.set_transition_weight("\0", &consonant, 0);
+            }
+        }
+
+        // Nothing the language starts a syllable with may follow "t", so it breaks the law
+        let previous = Syllable {
+            parts: vec![SyllablePart {
+                part_type: SyllablePartType::Consonant,
+                value: "t".to_string(),
+            }],
+        };
+        assert!(!lang.allows_cluster("t", "r", false));
+        let mut rng = Pcg64::seed_from_u64(0);
+        assert_eq!(lang.onset(&mut rng, Some(&previous)), "r");
+    }
+
+    #[test]
+    fn seam_keeps_onset() {
+        let mut lang = SynthLang::new(1);
+        lang.set_sonority_strictness(Strictness::Strict);
+        lang.add_phoneme("t", SyllablePartType::Consonant);
+        lang.add_phoneme("n", SyllablePartType::Consonant);
+        lang.add_phoneme("a", SyllablePartType::Vowel);
+        for consonant in lang.consonants.clone() {
+            if consonant != "n" {
+                lang.set_transition_weight(&consonant, "a", 0);
+            }
+        }
+
+        let part = |part_type, value: &str| SyllablePart {
+            part_type,
+            value: value.to_string(),
+        };
+        let mut parts = vec![Syllable {
+            parts: vec![
+                part(SyllablePartType::Vowel, "a"),
+                part(SyllablePartType::Consonant, "t"),
+            ],
+        }];
+        let next = Syllable {
+            parts: vec![
+                part(SyllablePartType::Consonant, "n"),
+                part(SyllablePartType::Vowel, "a"),
+            ],
+        };
+
+        // Only "n" may come before "a", so the seam keeps it rather than break a transition
+        assert!(!lang.allows_cluster("t", "n", false));
+        let mut rng = Pcg64::seed_from_u64(0);
+        lang.append_syllables(&mut rng, &mut parts, Some(next));
+        assert_eq!(parts[1].to_string(), "na");
+    }
+
+    #[test]
+    fn hierarchy() {
+        let mut lang = SynthLang::new(1);
+        let hierarchy = lang.sonority_hierarchy();
+        assert!(hierarchy.windows(2).all(|p| p[0].1 <= p[1].1));
+
+        assert!(lang.allows_cluster("t", "n", false));
+        lang.set_sonority_strictness(Strictness::Strict);
+        assert!(!lang.allows_cluster("t", "n", false));
+        assert!(lang.allows_cluster("t", "r", true));
+        assert!(!lang.allows_cluster("r", "t", true));
+    }
+}
@@ -0,0 +1,635 @@
+//! Ordered sound change rules written in the usual `target > replacement / left_right`
+//! notation, e.g. `p > f / V_V`, `k > ch / _[front]` or `[stop] > [fricative] / V_V`.
+
+use crate::phoneme::{self, Feature};
+use crate::{Syllable, SyllablePart, SyllablePartType, Word};
+use std::error::Error;
+use std::fmt;
+use std::str::FromStr;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Pattern {
+    Phoneme(String),
+    Vowel,
+    Consonant,
+    Features(Vec<(Feature, bool)>),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Context {
+    Boundary,
+    Pattern(Pattern),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Replacement {
+    Phoneme(String),
+    Features(Vec<(Feature, bool)>),
+    Delete,
+}
+
+/// A single rewrite rule. Parse one with `str::parse`.
+///
+/// The target and replacement may be a phoneme, a feature set in brackets (`[voiceless stop]`,
+/// `[-rounded]`) or `∅` (also written `0`) for insertion and deletion. Targets may also be `V`
+/// or `C`. The optional environment after `/` may contain phonemes, `V`, `C`, feature sets and
+/// `#` for a word boundary. Adjacent phonemes in an environment are separated by spaces.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SoundChange {
+    target: Option<Pattern>,
+    replacement: Replacement,
+    left: Vec<Context>,
+    right: Vec<Context>,
+}
+
+/// An ordered list of sound changes, applied one after another.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SoundChanges {
+    rules: Vec<SoundChange>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseSoundChangeError {
+    MissingArrow,
+    MissingTarget,
+    MissingReplacement,
+    MissingFocus,
+    UnclosedBracket,
+    UnknownFeature(String),
+    InvalidReplacement(String),
+    InvalidEnvironment(String),
+}
+
+impl fmt::Display for ParseSoundChangeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ParseSoundChangeError::MissingArrow => {
+                write!(f, "expected `>` between target and replacement")
+            }
+            ParseSoundChangeError::MissingTarget => write!(f, "missing target"),
+            ParseSoundChangeError::MissingReplacement => write!(f, "missing replacement"),
+            ParseSoundChangeError::MissingFocus => {
+                write!(f, "environment must contain exactly one `_`")
+            }
+            ParseSoundChangeError::UnclosedBracket => write!(f, "unclosed `[`"),
+            ParseSoundChangeError::UnknownFeature(s) => write!(f, "unknown feature `{}`", s),
+            ParseSoundChangeError::InvalidReplacement(s) => {
+                write!(f, "invalid replacement `{}`", s)
+            }
+            ParseSoundChangeError::InvalidEnvironment(s) => {
+                write!(f, "invalid environment `{}`", s)
+            }
+        }
+    }
+}
+
+impl Error for ParseSoundChangeError {}
+
+impl Pattern {
+    fn matches(&self, part: &SyllablePart) -> bool {
+        match self {
+            Pattern::Phoneme(p) => *p == part.value,
+            Pattern::Vowel => matches!(part.part_type, SyllablePartType::Vowel),
+            Pattern::Consonant => matches!(part.part_type, SyllablePartType::Consonant),
+            Pattern::Features(spec) => match phoneme::features(&part.value) {
+                Some(features) => spec
+                    .iter()
+                    .all(|(feature, value)| features.has(*feature) == *value),
+                None => false,
+            },
+        }
+    }
+}
+
+impl SoundChange {
+    /// Applies this rule everywhere it matches in `word`. Environments are always checked
+    /// against the word as it was before this rule was applied.
+    #[must_use]
+    pub fn apply(&self, word: &Word) -> Word {
+        self.apply_with_inventory(word, &[])
+    }
+
+    pub(crate) fn apply_with_inventory(&self, word: &Word, inventory: &[String]) -> Word {
+        // Flatten the word into (syllable index, part) so that environments can look across
+        // syllable boundaries.
+        let input: Vec<(usize, &SyllablePart)> = word
+            .parts
+            .iter()
+            .enumerate()
+            .flat_map(|(i, syllable)| syllable.parts.iter().map(move |p| (i, p)))
+            .collect();
+
+        let mut output: Vec<(usize, SyllablePart)> = vec![];
+
+        for i in 0..=input.len() {
+            if self.target.is_none() && self.matches_environment(&input, i, i) {
+                if let Replacement::Phoneme(value) = &self.replacement {
+                    let syllable = if i > 0 {
+                        input[i - 1].0
+                    } else {
+                        input.first().map_or(0, |p| p.0)
+                    };
+                    output.push((syllable, Self::part(value, SyllablePartType::Vowel)));
+                }
+            }
+
+            let (syllable, part) = match input.get(i) {
+                Some(p) => *p,
+                None => break,
+            };
+
+            let matched = match &self.target {
+                Some(target) => target.matches(part) && self.matches_environment(&input, i, i + 1),
+                None => false,
+            };
+
+            if !matched {
+                output.push((syllable, part.clone()));
+                continue;
+            }
+
+            if let Some(replaced) = self.replace(part, inventory) {
+                output.push((syllable, replaced));
+            }
+        }
+
+        let mut syllables: Vec<Syllable> = vec![];
+        let mut current = None;
+        for (i, part) in output {
+            if current != Some(i) {
+                syllables.push(Syllable { parts: vec![] });
+                current = Some(i);
+            }
+            syllables.last_mut().unwrap().parts.push(part);
+        }
+
+        Word::new(syllables, word.compound_rule)
+    }
+
+    /// What a matching `part` turns into, or `None` if it is deleted.
+    fn replace(&self, part: &SyllablePart, inventory: &[String]) -> Option<SyllablePart> {
+        match &self.replacement {
+            Replacement::Delete => None,
+            Replacement::Phoneme(value) => Some(Self::part(value, part.part_type)),
+            Replacement::Features(spec) => {
+                let changed = phoneme::features(&part.value)
+                    .and_then(|features| {
+                        spec.iter()
+                            .try_fold(features, |f, (feature, value)| f.with(*feature, *value))
+                    })
+                    .and_then(|features| phoneme::find(features, inventory));
+
+                match changed {
+                    Some(value) => Some(Self::part(&value, part.part_type)),
+                    None => Some(part.clone()),
+                }
+            }
+        }
+    }
+
+    /// Whether this rule only applies in some environment.
+    #[must_use]
+    pub fn is_conditional(&self) -> bool {
+        !self.left.is_empty() || !self.right.is_empty()
+    }
+
+    /// What `phoneme` can become under this rule, ignoring the environment. Returns `None` if
+    /// the rule never touches it and `Some(None)` if it is deleted. Insertions are reported
+    /// for every phoneme.
+    pub(crate) fn outcome(
+        &self,
+        phoneme: &str,
+        part_type: SyllablePartType,
+        inventory: &[String],
+    ) -> Option<Option<String>> {
+        let part = SyllablePart {
+            part_type,
+            value: phoneme.to_string(),
+        };
+
+        match &self.target {
+            Some(target) if target.matches(&part) => {
+                Some(self.replace(&part, inventory).map(|p| p.value))
+            }
+            Some(_) => None,
+            None => match &self.replacement {
+                Replacement::Phoneme(value) => Some(Some(value.clone())),
+                _ => None,
+            },
+        }
+    }
+
+    fn part(value: &str, fallback: SyllablePartType) -> SyllablePart {
+        let part_type = match phoneme::features(value) {
+            Some(f) if f.is_vowel() => SyllablePartType::Vowel,
+            Some(_) => SyllablePartType::Consonant,
+            None => fallback,
+        };
+
+        SyllablePart {
+            part_type,
+            value: value.to_string(),
+        }
+    }
+
+    fn matches_environment(
+        &self,
+        input: &[(usize, &SyllablePart)],
+        start: usize,
+        end: usize,
+    ) -> bool {
+        let mut i = start;
+        for context in self.left.iter().rev() {
+            match context {
+                Context::Boundary if i == 0 => {}
+                Context::Pattern(p) if i > 0 && p.matches(input[i - 1].1) => i -= 1,
+                _ => return false,
+            }
+        }
+
+        let mut i = end;
+        for context in &self.right {
+            match context {
+                Context::Boundary if i == input.len() => {}
+                Context::Pattern(p) if i < input.len() && p.matches(input[i].1) => i += 1,
+                _ => return false,
+            }
+        }
+
+        true
+    }
+}
+
+impl SoundChanges {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn push(&mut self, rule: SoundChange) {
+        self.rules.push(rule);
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &SoundChange> {
+        self.rules.iter()
+    }
+
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.rules.len()
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.rules.is_empty()
+    }
+
+    /// Applies every rule, in order, to `word`.
+    #[must_use]
+    pub fn apply(&self, word: &Word) -> Word {
+        self.apply_with_inventory(word, &[])
+    }
+
+    /// Applies every rule, in order, to each of `words`.
+    #[must_use]
+    pub fn apply_all(&self, words: &[Word]) -> Vec<Word> {
+        words.iter().map(|w| self.apply(w)).collect()
+    }
+
+    pub(crate) fn apply_with_inventory(&self, word: &Word, inventory: &[String]) -> Word {
+        self.rules.iter().fold(word.clone(), |w, rule| {
+            rule.apply_with_inventory(&w, inventory)
+        })
+    }
+}
+
+impl std::iter::FromIterator<SoundChange> for SoundChanges {
+    fn from_iter<I: IntoIterator<Item = SoundChange>>(iter: I) -> Self {
+        Self {
+            rules: iter.into_iter().collect(),
+        }
+    }
+}
+
+fn parse_features(s: &str) -> Result<Vec<(Feature, bool)>, ParseSoundChangeError> {
+    s.split(|c: char| c.is_whitespace() || c == ',')
+        .filter(|f| !f.is_empty())
+        .map(|f| {
+            let (name, value) = if let Some(name) = f.strip_prefix('-') {
+                (name, false)
+            } else if let Some(name) = f.strip_prefix('+') {
+                (name, true)
+            } else {
+                (f, true)
+            };
+
+            let (name, value) = match name {
+                "voiceless" => ("voiced", !value),
+                "unrounded" => ("rounded", !value),
+                "monophthong" => ("diphthong", !value),
+                _ => (name, value),
+            };
+
+            match name.parse::<Feature>() {
+                Ok(feature) if value || feature.is_binary() => Ok((feature, value)),
+                _ => Err(ParseSoundChangeError::UnknownFeature(f.to_string())),
+            }
+        })
+        .collect()
+}
+
+fn is_null(s: &str) -> bool {
+    matches!(s, "∅" | "0" | "Ø")
+}
+
+fn parse_pattern(s: &str) -> Result<Pattern, ParseSoundChangeError> {
+    match s {
+        "V" => Ok(Pattern::Vowel),
+        "C" => Ok(Pattern::Consonant),
+        _ => match s.strip_prefix('[') {
+            Some(inner) => match inner.strip_suffix(']') {
+                Some(inner) => Ok(Pattern::Features(parse_features(inner)?)),
+                None => Err(ParseSoundChangeError::UnclosedBracket),
+            },
+            None => Ok(Pattern::Phoneme(s.to_string())),
+        },
+    }
+}
+
+fn parse_context(s: &str) -> Result<Vec<Context>, ParseSoundChangeError> {
+    let mut contexts = vec![];
+    let mut chars = s.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            c if c.is_whitespace() => {}
+            '#' => contexts.push(Context::Boundary),
+            'V' => contexts.push(Context::Pattern(Pattern::Vowel)),
+            'C' => contexts.push(Context::Pattern(Pattern::Consonant)),
+            '[' => {
+                let mut inner = String::new();
+                loop {
+                    match chars.next() {
+                        Some(']') => break,
+                        Some(c) => inner.push(c),
+                        None => return Err(ParseSoundChangeError::UnclosedBracket),
+                    }
+                }
+                contexts.push(Context::Pattern(Pattern::Features(parse_features(&inner)?)));
+            }
+            ']' | '_' | '/' | '>' => {
+                return Err(ParseSoundChangeError::InvalidEnvironment(s.to_string()))
+            }
+            c => {
+                let mut phoneme = c.to_string();
+                while let Some(&c) = chars.peek() {
+                    if c.is_whitespace() || matches!(c, '#' | 'V' | 'C' | '[' | ']' | '_') {
+                        break;
+                    }
+                    phoneme.push(c);
+                    chars.next();
+                }
+                contexts.push(Context::Pattern(Pattern::Phoneme(phoneme)));
+            }
+        }
+    }
+
+    Ok(contexts)
+}
+
+impl FromStr for SoundChange {
+    type Err = ParseSoundChangeError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.replace("->", ">").replace('→', ">");
+
+        let (change, environment) = match s.find('/') {
+            Some(i) => (&s[..i], Some(&s[i + 1..])),
+            None => (&s[..], None),
+        };
+
+        let mut sides = change.split('>');
+        let (target, replacement) = match (sides.next(), sides.next(), sides.next()) {
+            (Some(t), Some(r), None) => (t.trim(), r.trim()),
+            _ => return Err(ParseSoundChangeError::MissingArrow),
+        };
+
+        if target.is_empty() {
+            return Err(ParseSoundChangeError::MissingTarget);
+        }
+        if replacement.is_empty() {
+            return Err(ParseSoundChangeError::MissingReplacement);
+        }
+
+        let target = if is_null(target) {
+            None
+        } else {
+            Some(parse_pattern(target)?)
+        };
+
+        let replacement = if is_null(replacement) {
+            Replacement::Delete
+        } else {
+            match parse_pattern(replacement)? {
+                Pattern::Phoneme(p) => Replacement::Phoneme(p),
+                Pattern::Features(spec)
+                    if target.is_some() && spec.iter().all(|(f, _)| f.is_settable()) =>
+                {
+                    Replacement::Features(spec)
+                }
+                _ => {
+                    return Err(ParseSoundChangeError::InvalidReplacement(
+                        replacement.to_string(),
+                    ))
+                }
+            }
+        };
+
+        if target.is_none() && !matches!(replacement, Replacement::Phoneme(_)) {
+            return Err(ParseSoundChangeError::InvalidReplacement(
+                change.trim().to_string(),
+            ));
+        }
+
+        let (left, right) = match environment {
+            Some(environment) => {
+                let mut sides = environment.split('_');
+                match (sides.next(), sides.next(), sides.next()) {
+                    (Some(l), Some(r), None) => (parse_context(l)?, parse_context(r)?),
+                    _ => return Err(ParseSoundChangeError::MissingFocus),
+                }
+            }
+            None if target.is_none() => return Err(ParseSoundChangeError::MissingFocus),
+            None => (vec![], vec![]),
+        };
+
+        Ok(Self {
+            target,
+            replacement,
+            left,
+            right,
+        })
+    }
+}
+
+impl FromStr for SoundChanges {
+    type Err = ParseSoundChangeError;
+
+    /// Parses one rule per line (or per `;`), skipping blank lines.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        s.split(['\n', ';'])
+            .map(str::trim)
+            .filter(|l| !l.is_empty())
+            .map(str::parse)
+            .collect()
+    }
+}
+
+impl fmt::Display for Pattern {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Pattern::Phoneme(p) => write!(f, "{}", p),
+            Pattern::Vowel => write!(f, "V"),
+            Pattern::Consonant => write!(f, "C"),
+            Pattern::Features(spec) => {
+                let spec: Vec<String> = spec
+                    .iter()
+                    .map(|(feature, value)| {
+                        if *value {
+                            feature.to_string()
+                        } else {
+                            format!("-{}", feature)
+                        }
+                    })
+                    .collect();
+                write!(f, "[{}]", spec.join(" "))
+            }
+        }
+    }
+}
+
+impl fmt::Display for SoundChange {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match &self.target {
+            Some(target) => write!(f, "{}", target)?,
+            None => write!(f, "∅")?,
+        }
+
+        match &self.replacement {
+            Replacement::Phoneme(p) => write!(f, " > {}", p)?,
+            Replacement::Features(spec) => write!(f, " > {}", Pattern::Features(spec.clone()))?,
+            Replacement::Delete => write!(f, " > ∅")?,
+        }
+
+        if self.left.is_empty() && self.right.is_empty() {
+            return Ok(());
+        }
+
+        let context = |contexts: &[Context]| -> Vec<String> {
+            contexts
+                .iter()
+                .map(|c| match c {
+                    Context::Boundary => "#".to_string(),
+                    Context::Pattern(p) => p.to_string(),
+                })
+                .collect()
+        };
+
+        write!(
+            f,
+            " / {}_{}",
+            context(&self.left).join(" "),
+            context(&self.right).join(" ")
+        )
+    }
+}
+
+impl fmt::Display for SoundChanges {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for rule in &self.rules {
+            writeln!(f, "{}", rule)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::CompoundRule;
+
+    fn word(syllables: &[&[&str]]) -> Word {
+        Word {
+            parts: syllables
+                .iter()
+                .map(|s| Syllable {
+                    parts: s
+                        .iter()
+                        .map(|p| SoundChange::part(p, SyllablePartType::Consonant))
+                        .collect(),
+                })
+                .collect(),
+            compound_rule: CompoundRule::DropNone,
+        }
+    }
+
+    #[test]
+    fn intervocalic_lenition() {
+        let rule: SoundChange = "p > f / V_V".parse().unwrap();
+
+        assert_eq!(
+            rule.apply(&word(&[&["p", "a"], &["p", "a"]])).to_string(),
+            "pafa"
+        );
+
+        let rule: SoundChange = "[stop] > [fricative] / V_V".parse().unwrap();
+
+        assert_eq!(
+            rule.apply(&word(&[&["a", "k"], &["t", "a"]])).to_string(),
+            "akta"
+        );
+        assert_eq!(rule.apply(&word(&[&["a"], &["k", "a"]])).to_string(), "axa");
+    }
+
+    #[test]
+    fn ordered_rules() {
+        let changes: SoundChanges = "k > ch / _[front]\n e > a / _#\n ∅ > e / #_s"
+            .parse()
+            .unwrap();
+
+        assert_eq!(changes.len(), 3);
+        assert_eq!(
+            changes.apply(&word(&[&["s", "k", "e"]])).to_string(),
+            "escha"
+        );
+    }
+
+    #[test]
+    fn round_trip() {
+        for s in &[
+            "p > f / V_V",
+            "k > ch / _[front]",
+            "∅ > e / # s_t",
+            "[-voiced] > ∅ / _#",
+        ] {
+            let rule: SoundChange = s.parse().unwrap();
+            assert_eq!(rule.to_string().parse::<SoundChange>().unwrap(), rule);
+        }
+    }
+
+    #[test]
+    fn errors() {
+        assert_eq!(
+            "p f".parse::<SoundChange>(),
+            Err(ParseSoundChangeError::MissingArrow)
+        );
+        assert_eq!(
+            "p > f / V".parse::<SoundChange>(),
+            Err(ParseSoundChangeError::MissingFocus)
+        );
+        assert_eq!(
+            "[sparkly] > f".parse::<SoundChange>(),
+            Err(ParseSoundChangeError::UnknownFeature("sparkly".to_string()))
+        );
+    }
+}
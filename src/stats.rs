@@ -0,0 +1,169 @@
+//! How much variety a language's words have, worked out from its weights rather than by
+//! sampling.
+
+use crate::{Syllable, SyllablePart, SyllablePartType, SynthLang, Word, WORD_LENGTHS};
+use std::collections::HashSet;
+
+/// Statistics about the words `SynthLang::word()` generates. Entropies are in bits.
+///
+/// Words are counted by their syllables, so two words that happen to be spelled the same
+/// count as different. The sonority strictness and any checks on generated words are not
+/// taken into account.
+#[derive(Debug, Clone)]
+pub struct Statistics {
+    pub entropy_per_word: f64,
+    pub entropy_per_phoneme: f64,
+    pub expected_syllables: f64,
+    pub expected_phonemes: f64,
+    pub expected_letters: f64,
+    /// How many differently spelled syllables the language can generate.
+    pub distinct_syllables: usize,
+    /// The probability that two generated words are the same.
+    pub collision_probability: f64,
+}
+
+impl Statistics {
+    /// At most how many distinct words of `syllables` syllables the language can generate.
+    #[must_use]
+    pub fn distinct_words(&self, syllables: usize) -> u64 {
+        (self.distinct_syllables as u64).saturating_pow(syllables as u32)
+    }
+
+    /// Roughly how many pairs of words would be the same among `words` generated words.
+    #[must_use]
+    pub fn expected_collisions(&self, words: usize) -> f64 {
+        let pairs = words as f64 * words.saturating_sub(1) as f64 / 2.0;
+        pairs * self.collision_probability
+    }
+}
+
+impl SynthLang {
+    /// The probability that `word()` generates `word` with exactly these syllables.
+    #[must_use]
+    pub fn probability(&self, word: &Word) -> f64 {
+        let total: i32 = WORD_LENGTHS.iter().map(|l| l.1).sum();
+        let length = WORD_LENGTHS
+            .iter()
+            .find(|l| l.0 == word.parts.len())
+            .map_or(0, |l| l.1);
+
+        f64::from(length) / f64::from(total) * self.log_probability(word).exp()
+    }
+
+    #[must_use]
+    pub fn statistics(&self) -> Statistics {
+        let syllables = self.syllable_distribution();
+
+        let syllable_entropy: f64 = syllables.iter().map(|(_, p)| -p * p.log2()).sum();
+        let syllable_phonemes: f64 = syllables
+            .iter()
+            .map(|(s, p)| p * s.parts.len() as f64)
+            .sum();
+        let syllable_letters: f64 = syllables
+            .iter()
+            .map(|(s, p)| p * s.to_string().chars().count() as f64)
+            .sum();
+        let syllable_collision: f64 = syllables.iter().map(|(_, p)| p * p).sum();
+
+        let total: i32 = WORD_LENGTHS.iter().map(|l| l.1).sum();
+        let lengths: Vec<(f64, f64)> = WORD_LENGTHS
+            .iter()
+            .map(|(n, w)| (*n as f64, f64::from(*w) / f64::from(total)))
+            .collect();
+
+        let expected_syllables: f64 = lengths.iter().map(|(n, p)| n * p).sum();
+        let length_entropy: f64 = lengths.iter().map(|(_, p)| -p * p.log2()).sum();
+        let entropy_per_word = length_entropy + expected_syllables * syllable_entropy;
+        let expected_phonemes = expected_syllables * syllable_phonemes;
+
+        Statistics {
+            entropy_per_word,
+            entropy_per_phoneme: entropy_per_word / expected_phonemes,
+            expected_syllables,
+            expected_phonemes,
+            expected_letters: expected_syllables * syllable_letters,
+            distinct_syllables: syllables
+                .iter()
+                .map(|(s, _)| s.to_string())
+                .collect::<HashSet<_>>()
+                .len(),
+            collision_probability: lengths
+                .iter()
+                .map(|(n, p)| p * p * syllable_collision.powi(*n as i32))
+                .sum(),
+        }
+    }
+
+    /// Every syllable this language can generate, with its probability.
+    fn syllable_distribution(&self) -> Vec<(Syllable, f64)> {
+        let mut syllables = vec![];
+
+        for template in self.templates() {
+            let mut partial: Vec<Vec<SyllablePart>> = vec![vec![]];
+            for part_type in template {
+                let candidates = match part_type {
+                    SyllablePartType::Vowel => &self.vowels,
+                    SyllablePartType::Consonant => &self.consonants,
+                };
+
+                partial = partial
+                    .into_iter()
+                    .flat_map(|parts| {
+                        let previous = parts.last().map_or("\0", |p| p.value.as_str());
+                        candidates
+                            .iter()
+                            .filter(|c| self.transition_probability(previous, c, part_type) > 0.0)
+                            .map(|c| {
+                                let mut parts = parts.clone();
+                                parts.push(SyllablePart {
+                                    part_type,
+                                    value: c.clone(),
+                                });
+                                parts
+                            })
+                            .collect::<Vec<_>>()
+                    })
+                    .collect();
+            }
+
+            syllables.extend(partial.into_iter().map(|parts| {
+                let syllable = Syllable { parts };
+                let p = self.syllable_log_probability(&syllable).exp();
+                (syllable, p)
+            }));
+        }
+
+        syllables
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn statistics() {
+        let mut lang = SynthLang::new(6);
+        let stats = lang.statistics();
+
+        let total: f64 = lang.syllable_distribution().iter().map(|(_, p)| p).sum();
+        assert!((total - 1.0).abs() < 1e-9);
+
+        assert!((stats.expected_syllables - 5.0 / 3.0).abs() < 1e-9);
+        assert!(stats.expected_phonemes > stats.expected_syllables * 2.0);
+        assert!(stats.entropy_per_phoneme > 0.0);
+        assert!(stats.entropy_per_word > stats.entropy_per_phoneme);
+        assert!(stats.distinct_words(2) > stats.distinct_words(1));
+
+        // Compare with how often words actually repeat
+        let words: Vec<String> = (0..2000).map(|_| lang.word().to_string()).collect();
+        let distinct: HashSet<&String> = words.iter().collect();
+        let repeats = (words.len() - distinct.len()) as f64;
+        let expected = stats.expected_collisions(words.len());
+        assert!(repeats < expected * 2.0 + 10.0 && repeats > expected / 2.0 - 10.0);
+
+        let word = lang.word();
+        assert!(lang.probability(&word) > 0.0);
+        assert!(lang.probability(&word) < lang.log_probability(&word).exp());
+    }
+}
@@ -0,0 +1,218 @@
+//! Placeholder text: sentences and paragraphs that look like running prose in a language.
+
+use crate::{Casing, SynthLang, Word};
+use rand::prelude::*;
+use rand_pcg::Pcg64;
+
+const FUNCTION_WORDS: usize = 24;
+
+// How many words are tried before settling for fewer function words, or for a word repeating
+const ATTEMPTS: usize = 10;
+
+/// Generates filler prose for book pages, signs and inscriptions.
+///
+/// Words go through the same checks as `SynthLang::word()`: the content filter, the minimum
+/// pronounceability and the uniqueness tracker, which is consulted but not added to.
+///
+/// Like real text, a small set of short function words makes up a large share of every
+/// sentence, and both function and content words are reused with a Zipfian distribution, so a
+/// few words are very common and most are rare.
+#[derive(Debug, Clone)]
+pub struct TextGenerator<'a> {
+    lang: &'a SynthLang,
+    casing: Casing,
+    rng: Pcg64,
+    function_words: Vec<Word>,
+    content_words: Vec<Word>,
+}
+
+impl<'a> TextGenerator<'a> {
+    #[must_use]
+    pub fn new(lang: &'a SynthLang, seed: u64) -> Self {
+        let mut rng = Pcg64::seed_from_u64(seed);
+
+        // A small inventory or a strict filter may not have enough monosyllables to go round
+        let mut function_words: Vec<Word> = vec![];
+        for _ in 0..FUNCTION_WORDS * ATTEMPTS {
+            if function_words.len() == FUNCTION_WORDS {
+                break;
+            }
+
+            let word = lang.generate_acceptable_word_with_lengths(&mut rng, &[(1, 1)]);
+            if !function_words
+                .iter()
+                .any(|w| w.to_string() == word.to_string())
+            {
+                function_words.push(word);
+            }
+        }
+        // The shortest words are the most frequent ones
+        function_words.sort_by_key(|w| w.to_string().chars().count());
+
+        Self {
+            lang,
+            casing: lang.casing(),
+            rng,
+            function_words,
+            content_words: vec![],
+        }
+    }
+
+    /// A single sentence, capitalized and punctuated.
+    pub fn sentence(&mut self) -> String {
+        let len = self.rng.gen_range(4, 15);
+
+        let mut words: Vec<String> = vec![];
+        for i in 0..len {
+            let mut word = self.next_word();
+            for _ in 0..ATTEMPTS {
+                if words.last().map_or(true, |last| *last != word) {
+                    break;
+                }
+                word = self.next_word();
+            }
+
+            if i > 1 && i + 2 < len && self.rng.gen_bool(0.08) {
+                word.push(',');
+            }
+            words.push(word);
+        }
+
+        let mut text = self.casing.capitalize(&words.join(" "));
+        text.push(
+            *[('.', 85), ('?', 8), ('!', 7)]
+                .choose_weighted(&mut self.rng, |p| p.1)
+                .map(|p| &p.0)
+                .unwrap(),
+        );
+
+        text
+    }
+
+    /// A paragraph of a few sentences.
+    pub fn paragraph(&mut self) -> String {
+        let len = self.rng.gen_range(3, 8);
+        (0..len)
+            .map(|_| self.sentence())
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+
+    /// `count` paragraphs separated by blank lines.
+    pub fn paragraphs(&mut self, count: usize) -> String {
+        (0..count)
+            .map(|_| self.paragraph())
+            .collect::<Vec<_>>()
+            .join("\n\n")
+    }
+
+    fn next_word(&mut self) -> String {
+        if self.rng.gen_bool(0.45) {
+            let i = self.zipf(self.function_words.len(), 1.0);
+            return self.function_words[i].to_string();
+        }
+
+        // New words keep turning up, but less and less often as the text goes on
+        let known = self.content_words.len();
+        if known == 0 || self.rng.gen_bool(8.0 / (8.0 + (known as f64).sqrt() * 4.0)) {
+            let word = self.lang.generate_acceptable_word_with_lengths(
+                &mut self.rng,
+                &[(1, 3), (2, 5), (3, 3), (4, 1)],
+            );
+            // Give the new word a random rank, so the most common words aren't simply the
+            // first ones in the text
+            let rank = self.rng.gen_range(0, known + 1);
+            self.content_words.insert(rank, word);
+            return self.content_words[rank].to_string();
+        }
+
+        let i = self.zipf(known, 4.0);
+        self.content_words[i].to_string()
+    }
+
+    /// Picks an index below `n`, where index `i` is picked with probability proportional to
+    /// `1 / (i + offset)`. Larger offsets flatten the head of the distribution.
+    fn zipf(&mut self, n: usize, offset: f64) -> usize {
+        let weight = |i: usize| 1.0 / (i as f64 + offset);
+        let total: f64 = (0..n).map(weight).sum();
+        let mut target = self.rng.gen_range(0.0, total);
+
+        for i in 0..n {
+            target -= weight(i);
+            if target < 0.0 {
+                return i;
+            }
+        }
+
+        n - 1
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    #[test]
+    fn paragraphs() {
+        let lang = SynthLang::new(2);
+
+        let text = TextGenerator::new(&lang, 1).paragraphs(3);
+        assert_eq!(text, TextGenerator::new(&lang, 1).paragraphs(3));
+        assert_eq!(text.split("\n\n").count(), 3);
+
+        for paragraph in text.split("\n\n") {
+            assert!(paragraph.chars().next().unwrap().is_uppercase());
+            assert!(paragraph.ends_with(['.', '?', '!']));
+        }
+
+        let mut counts: HashMap<&str, usize> = HashMap::new();
+        let words: Vec<&str> = text
+            .split_whitespace()
+            .map(|w| w.trim_end_matches([',', '.', '?', '!']))
+            .collect();
+        for word in &words {
+            *counts.entry(word).or_default() += 1;
+        }
+
+        // A handful of words should make up a good part of the text
+        let mut counts: Vec<usize> = counts.values().cloned().collect();
+        counts.sort_unstable_by(|a, b| b.cmp(a));
+        assert!(counts.iter().take(5).sum::<usize>() * 5 > words.len());
+    }
+
+    #[test]
+    fn small_inventory() {
+        let mut lang = SynthLang::new(2);
+        while lang.vowels.len() > 1 {
+            let vowel = lang.vowels[0].clone();
+            lang.remove_phoneme(&vowel);
+        }
+        while lang.consonants.len() > 1 {
+            let consonant = lang.consonants[0].clone();
+            lang.remove_phoneme(&consonant);
+        }
+        lang.vc_weight = 0;
+        lang.cvc_weight = 0;
+
+        let mut generator = TextGenerator::new(&lang, 1);
+        assert_eq!(generator.function_words.len(), 1);
+        assert_eq!(generator.paragraphs(2).split("\n\n").count(), 2);
+    }
+
+    #[test]
+    fn checked() {
+        let mut lang = SynthLang::new(2);
+        lang.set_min_pronounceability(Some(0.6));
+        let mut blocklist = crate::Blocklist::empty();
+        blocklist.set_phonetic(false);
+        blocklist.add_substring(&lang.vowels[0]);
+        lang.set_content_filter(Some(std::sync::Arc::new(blocklist)));
+
+        let text = TextGenerator::new(&lang, 1).paragraphs(3);
+        for word in text.split_whitespace() {
+            let word = word.trim_end_matches([',', '.', '?', '!']).to_lowercase();
+            assert!(!word.contains(lang.vowels[0].as_str()), "{}", word);
+        }
+    }
+}
@@ -0,0 +1,406 @@
+//! Word-by-word pseudo-translation of free English text, with an interlinear gloss.
+
+use crate::grammar::Grammar;
+use crate::vocabulary::{self, SemanticField};
+use crate::{Casing, Lexicon, SynthLang, Word};
+
+// (inflected form, lemma, plural, past)
+const IRREGULAR: &[(&str, &str, bool, bool)] = &[
+    ("men", "man", true, false),
+    ("women", "woman", true, false),
+    ("children", "child", true, false),
+    ("people", "people", false, false),
+    ("feet", "foot", true, false),
+    ("teeth", "tooth", true, false),
+    ("geese", "goose", true, false),
+    ("mice", "mouse", true, false),
+    ("lice", "louse", true, false),
+    ("wolves", "wolf", true, false),
+    ("knives", "knife", true, false),
+    ("leaves", "leaf", true, false),
+    ("lives", "life", true, false),
+    ("wives", "wife", true, false),
+    ("elves", "elf", true, false),
+    ("dwarves", "dwarf", true, false),
+    ("is", "be", false, false),
+    ("are", "be", false, false),
+    ("am", "be", false, false),
+    ("was", "be", false, true),
+    ("were", "be", false, true),
+    ("has", "have", false, false),
+    ("had", "have", false, true),
+    ("does", "do", false, false),
+    ("did", "do", false, true),
+    ("went", "go", false, true),
+    ("came", "come", false, true),
+    ("saw", "see", false, true),
+    ("ate", "eat", false, true),
+    ("drank", "drink", false, true),
+    ("knew", "know", false, true),
+    ("thought", "think", false, true),
+    ("slept", "sleep", false, true),
+    ("fought", "fight", false, true),
+    ("flew", "fly", false, true),
+    ("swam", "swim", false, true),
+    ("sat", "sit", false, true),
+    ("stood", "stand", false, true),
+    ("fell", "fall", false, true),
+    ("gave", "give", false, true),
+    ("held", "hold", false, true),
+    ("threw", "throw", false, true),
+    ("said", "say", false, true),
+    ("sang", "sing", false, true),
+    ("froze", "freeze", false, true),
+    ("bit", "bite", false, true),
+    ("spat", "spit", false, true),
+    ("blew", "blow", false, true),
+    ("heard", "hear", false, true),
+    ("made", "make", false, true),
+    ("took", "take", false, true),
+    ("found", "find", false, true),
+    ("told", "tell", false, true),
+    ("left", "leave", false, true),
+    ("lay", "lie", false, true),
+    ("rode", "ride", false, true),
+    ("spoke", "speak", false, true),
+    ("wrote", "write", false, true),
+    ("me", "i", false, false),
+    ("my", "i", false, false),
+    ("him", "he", false, false),
+    ("his", "he", false, false),
+    ("her", "she", false, false),
+    ("its", "it", false, false),
+    ("us", "we", false, false),
+    ("our", "we", false, false),
+    ("your", "you", false, false),
+    ("them", "they", false, false),
+    ("their", "they", false, false),
+];
+
+/// A translated text, together with its word-by-word gloss.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Translation {
+    /// The translated text, with the source's punctuation and spacing.
+    pub text: String,
+    /// The source text with every word replaced by its lemma and grammatical tags, such as
+    /// `wolf-PL`.
+    pub gloss: String,
+    /// (translated word, gloss) for every word, in order.
+    pub words: Vec<(String, String)>,
+}
+
+impl Translation {
+    /// Renders the words and their glosses as two aligned lines.
+    #[must_use]
+    pub fn interlinear(&self) -> String {
+        let mut top = String::new();
+        let mut bottom = String::new();
+
+        for (word, gloss) in &self.words {
+            let width = word.chars().count().max(gloss.chars().count());
+            top.push_str(&format!("{:width$} ", word, width = width));
+            bottom.push_str(&format!("{:width$} ", gloss, width = width));
+        }
+
+        format!("{}\n{}", top.trim_end(), bottom.trim_end())
+    }
+}
+
+/// Translates English text word by word, minting words for unknown lemmas as it goes.
+///
+/// Every lemma goes through a `Lexicon`, so the same text always comes out the same for a given
+/// language, and a word keeps its translation across calls.
+#[derive(Debug, Clone)]
+pub struct Translator<'a> {
+    lang: &'a SynthLang,
+    grammar: Grammar,
+    casing: Casing,
+    lexicon: Lexicon,
+    preserve_proper_nouns: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Case {
+    Lower,
+    Capitalized,
+    Upper,
+}
+
+impl<'a> Translator<'a> {
+    #[must_use]
+    pub fn new(lang: &'a SynthLang) -> Self {
+        Self::with_lexicon(lang, Lexicon::new())
+    }
+
+    /// A translator that starts from an existing lexicon, e.g. `SynthLang::dictionary`.
+    #[must_use]
+    pub fn with_lexicon(lang: &'a SynthLang, lexicon: Lexicon) -> Self {
+        Self {
+            lang,
+            grammar: lang.grammar(),
+            casing: lang.casing(),
+            lexicon,
+            preserve_proper_nouns: false,
+        }
+    }
+
+    /// Keeps capitalized words in the middle of a sentence (names, mostly) untranslated.
+    pub fn set_preserve_proper_nouns(&mut self, preserve: bool) {
+        self.preserve_proper_nouns = preserve;
+    }
+
+    #[must_use]
+    pub fn lexicon(&self) -> &Lexicon {
+        &self.lexicon
+    }
+
+    #[must_use]
+    pub fn into_lexicon(self) -> Lexicon {
+        self.lexicon
+    }
+
+    pub fn translate(&mut self, text: &str) -> Translation {
+        let mut translated = String::new();
+        let mut gloss = String::new();
+        let mut words = vec![];
+        let mut sentence_start = true;
+
+        for (token, is_word) in tokenize(text) {
+            if !is_word {
+                if token.contains(['.', '!', '?']) {
+                    sentence_start = true;
+                }
+                translated.push_str(token);
+                gloss.push_str(token);
+                continue;
+            }
+
+            let case = case_of(token);
+            let (word, tags) =
+                if self.preserve_proper_nouns && case != Case::Lower && !sentence_start {
+                    (token.to_string(), token.to_string())
+                } else {
+                    self.translate_word(&token.to_lowercase(), case)
+                };
+
+            translated.push_str(&word);
+            gloss.push_str(&tags);
+            words.push((word, tags));
+            sentence_start = false;
+        }
+
+        Translation {
+            text: translated,
+            gloss,
+            words,
+        }
+    }
+
+    fn translate_word(&mut self, lowercase: &str, case: Case) -> (String, String) {
+        let (lemma, plural, past) = lemmatize(lowercase, |lemma| {
+            vocabulary::concept(lemma).is_some() || self.lexicon.get(lemma).is_some()
+        });
+
+        let mut word: Word = self.lexicon.word(self.lang, &lemma).clone();
+        let mut tags = lemma.replace(' ', ".");
+        if plural {
+            word = self.lang.inflect(&self.grammar.plural, &word);
+            tags.push_str("-PL");
+        }
+        if past {
+            word = self.lang.inflect(&self.grammar.past, &word);
+            tags.push_str("-PST");
+        }
+        if lowercase.ends_with("'s") || lowercase.ends_with("s'") {
+            word = self.lang.inflect(&self.grammar.possessive, &word);
+            tags.push_str("-POSS");
+        }
+
+        let word = word.to_string();
+        let word = match case {
+            Case::Lower => word,
+            Case::Upper => word.to_uppercase(),
+            Case::Capitalized => self.casing.capitalize(&word),
+        };
+
+        (word, tags)
+    }
+}
+
+impl SynthLang {
+    /// Translates English text word by word with a fresh lexicon. Use a `Translator` to keep
+    /// words consistent across several texts or to preserve proper nouns.
+    #[must_use]
+    pub fn translate_text(&self, text: &str) -> Translation {
+        Translator::new(self).translate(text)
+    }
+}
+
+/// Splits text into runs of word characters (`true`) and everything else (`false`).
+fn tokenize(text: &str) -> Vec<(&str, bool)> {
+    let is_word_char = |c: char| c.is_alphanumeric() || c == '\'';
+
+    let mut tokens = vec![];
+    let mut start = 0;
+    let mut in_word = None;
+
+    for (i, c) in text.char_indices() {
+        let word = is_word_char(c);
+        if in_word.is_some_and(|w| w != word) {
+            tokens.push((&text[start..i], !word));
+            start = i;
+        }
+        in_word = Some(word);
+    }
+    if let Some(word) = in_word {
+        tokens.push((&text[start..], word));
+    }
+
+    tokens
+}
+
+fn case_of(token: &str) -> Case {
+    let letters: Vec<char> = token.chars().filter(|c| c.is_alphabetic()).collect();
+
+    if letters.len() > 1 && letters.iter().all(|c| c.is_uppercase()) {
+        Case::Upper
+    } else if letters.first().is_some_and(|c| c.is_uppercase()) {
+        Case::Capitalized
+    } else {
+        Case::Lower
+    }
+}
+
+/// Returns (lemma, plural, past) for a lowercase English word. Suffixes are only stripped when
+/// that leaves a lemma `known` accepts, so "hundred" isn't the past tense of "hundr".
+fn lemmatize<F>(word: &str, known: F) -> (String, bool, bool)
+where
+    F: Fn(&str) -> bool,
+{
+    let word = word.trim_end_matches("'s").trim_end_matches('\'');
+
+    if let Some((_, lemma, plural, past)) = IRREGULAR.iter().find(|i| i.0 == word) {
+        return (lemma.to_string(), *plural, *past);
+    }
+    if word == "you" || known(word) {
+        return (word.to_string(), false, false);
+    }
+
+    let is_verb =
+        |lemma: &str| vocabulary::concept(lemma).is_some_and(|c| c.field == SemanticField::Action);
+
+    // Past tense: "hunted", "lived", "stabbed", "carried"
+    if let Some(stem) = word
+        .strip_suffix("ed")
+        .filter(|s| s.len() > 1 && !s.ends_with('e'))
+    {
+        let candidates = [
+            stem.to_string(),
+            format!("{}e", stem),
+            stem.char_indices()
+                .next_back()
+                .map_or("", |(i, _)| &stem[..i])
+                .to_string(),
+            stem.strip_suffix('i')
+                .map(|s| format!("{}y", s))
+                .unwrap_or_default(),
+        ];
+        if let Some(lemma) = candidates.iter().find(|c| known(c)) {
+            return (lemma.clone(), false, true);
+        }
+    }
+
+    // Plurals, or third person verbs which are left uninflected
+    let mut candidates = vec![];
+    if let Some(stem) = word.strip_suffix("ies").filter(|s| s.len() > 1) {
+        candidates.push(format!("{}y", stem));
+    }
+    if let Some(stem) = word
+        .strip_suffix("es")
+        .filter(|s| ["s", "x", "z", "ch", "sh"].iter().any(|e| s.ends_with(e)))
+    {
+        candidates.push(stem.to_string());
+    }
+    if let Some(stem) = word
+        .strip_suffix('s')
+        .filter(|s| s.len() > 2 && !s.ends_with('s') && !s.ends_with('u') && !s.ends_with('i'))
+    {
+        candidates.push(stem.to_string());
+    }
+
+    if let Some(lemma) = candidates.iter().find(|c| known(c)) {
+        return (lemma.clone(), !is_verb(lemma), false);
+    }
+
+    (word.to_string(), false, false)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lemmatize(word: &str) -> (String, bool, bool) {
+        super::lemmatize(word, |lemma| {
+            ["café", "berry"].contains(&lemma) || vocabulary::concept(lemma).is_some()
+        })
+    }
+
+    #[test]
+    fn lemmas() {
+        assert_eq!(lemmatize("wolves"), ("wolf".to_string(), true, false));
+        assert_eq!(lemmatize("hunted"), ("hunt".to_string(), false, true));
+        assert_eq!(lemmatize("lived"), ("live".to_string(), false, true));
+        assert_eq!(lemmatize("stabbed"), ("stab".to_string(), false, true));
+        assert_eq!(lemmatize("berries"), ("berry".to_string(), true, false));
+        assert_eq!(lemmatize("eats"), ("eat".to_string(), false, false));
+        assert_eq!(lemmatize("glass"), ("glass".to_string(), false, false));
+        assert_eq!(lemmatize("saw"), ("see".to_string(), false, true));
+        assert_eq!(lemmatize("caféed"), ("café".to_string(), false, true));
+        assert_eq!(lemmatize("naïveté"), ("naïveté".to_string(), false, false));
+        assert_eq!(lemmatize("wicked"), ("wicked".to_string(), false, false));
+        assert_eq!(
+            lemmatize("hundreds"),
+            ("hundreds".to_string(), false, false)
+        );
+    }
+
+    #[test]
+    fn translate_text() {
+        let lang = SynthLang::new(9);
+        let text = "The wolves hunted in the forest. Where is Aldric?";
+
+        let translation = lang.translate_text(text);
+        assert_eq!(translation, lang.translate_text(text));
+        assert_eq!(
+            translation.gloss,
+            "the wolf-PL hunt-PST in the forest. where be aldric?"
+        );
+        assert_eq!(translation.words.len(), 9);
+        assert!(translation.text.ends_with('?'));
+        assert!(translation.text.contains(". "));
+        assert!(translation.text.chars().next().unwrap().is_uppercase());
+        assert_eq!(translation.interlinear().lines().count(), 2);
+        assert_eq!(
+            lang.translate_text("The café was caféed").gloss,
+            "the café be-PST café-PST"
+        );
+        assert_eq!(
+            lang.translate_text("the sacred wicked hundred").gloss,
+            "the sacred wicked hundred"
+        );
+        assert_eq!(
+            lang.translate_text("a hundred hundreds").gloss,
+            "a hundred hundred-PL"
+        );
+        assert_eq!(
+            lang.translate_text("The wolves' den").gloss,
+            "the wolf-PL-POSS den"
+        );
+
+        let mut translator = Translator::new(&lang);
+        translator.set_preserve_proper_nouns(true);
+        let translation = translator.translate(text);
+        assert!(translation.text.ends_with(" Aldric?"));
+        assert_eq!(translation.words[0].0, lang.translate_text(text).words[0].0);
+    }
+}
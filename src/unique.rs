@@ -0,0 +1,263 @@
+//! Optional tracking of generated words, so that `word()` and `compound()` never hand out the
+//! same word twice, or two words that are confusingly similar, along with the regeneration
+//! shared by every check on generated words.
+
+use crate::{Syllable, SynthLang, Word, WORD_LENGTHS};
+use rand_pcg::Pcg64;
+use std::collections::HashSet;
+
+// How many times a word is regenerated before trying longer words
+const ATTEMPTS: usize = 100;
+
+/// What two words are compared by.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Similarity {
+    /// Letters of the spelled word.
+    Spelling,
+    /// Phonemes, so that "sh" counts as a single difference.
+    Phonemes,
+}
+
+/// Remembers words and rejects new ones that are too close to any of them.
+#[derive(Debug, Clone)]
+pub struct UniquenessTracker {
+    similarity: Similarity,
+    min_distance: usize,
+    seen: HashSet<Vec<String>>,
+}
+
+impl UniquenessTracker {
+    /// Words closer than `min_distance` edits to a tracked word are rejected. A distance of 1
+    /// only rejects exact duplicates.
+    #[must_use]
+    pub fn new(similarity: Similarity, min_distance: usize) -> Self {
+        Self {
+            similarity,
+            min_distance: min_distance.max(1),
+            seen: HashSet::new(),
+        }
+    }
+
+    /// Whether `word` is the same as, or too similar to, a tracked word.
+    #[must_use]
+    pub fn is_taken(&self, word: &Word) -> bool {
+        let units = self.units(word);
+        if self.seen.contains(&units) {
+            return true;
+        }
+
+        self.min_distance > 1
+            && self.seen.iter().any(|seen| {
+                seen.len().abs_diff(units.len()) < self.min_distance
+                    && edit_distance(seen, &units) < self.min_distance
+            })
+    }
+
+    /// Starts tracking `word`, e.g. roots that compounds must not collide with.
+    pub fn insert(&mut self, word: &Word) {
+        let units = self.units(word);
+        self.seen.insert(units);
+    }
+
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.seen.len()
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.seen.is_empty()
+    }
+
+    fn units(&self, word: &Word) -> Vec<String> {
+        match self.similarity {
+            Similarity::Spelling => word.to_string().chars().map(String::from).collect(),
+            Similarity::Phonemes => word
+                .parts
+                .iter()
+                .flat_map(|s| s.parts.iter())
+                .map(|p| p.value.clone())
+                .collect(),
+        }
+    }
+}
+
+impl SynthLang {
+    /// Turns on uniqueness tracking for `word()` and `compound()`, or turns it off with `None`.
+    pub fn set_uniqueness_tracker(&mut self, tracker: Option<UniquenessTracker>) {
+        self.uniqueness = tracker;
+    }
+
+    #[must_use]
+    pub fn uniqueness_tracker(&self) -> Option<&UniquenessTracker> {
+        self.uniqueness.as_ref()
+    }
+
+    pub fn uniqueness_tracker_mut(&mut self) -> Option<&mut UniquenessTracker> {
+        self.uniqueness.as_mut()
+    }
+
+    /// Whether `word()` and `compound()` need to check what they generate.
+    pub(crate) fn checks_words(&self) -> bool {
+        self.uniqueness.is_some()
+            || self.content_filter.is_some()
+            || self.min_pronounceability.is_some()
+    }
+
+    /// Whether `word` is not taken in `tracker`, not blocked by the content filter and
+    /// pronounceable enough.
+    pub(crate) fn is_acceptable(&self, tracker: Option<&UniquenessTracker>, word: &Word) -> bool {
+        tracker.map_or(true, |t| !t.is_taken(word))
+            && !self.is_blocked(word)
+            && self
+                .min_pronounceability
+                .map_or(true, |min| word.pronounceability() >= min)
+    }
+
+    /// Whether to accept `word` on the given attempt. After `patience` attempts, uniqueness and
+    /// pronounceability are given up on, but the content filter never is.
+    pub(crate) fn accepts(
+        &self,
+        tracker: Option<&UniquenessTracker>,
+        word: &Word,
+        attempt: usize,
+        patience: usize,
+    ) -> bool {
+        if attempt < patience {
+            self.is_acceptable(tracker, word)
+        } else {
+            !self.is_blocked(word)
+        }
+    }
+
+    /// Calls `generate` with the number of each attempt until it returns a word that `accepts`
+    /// takes. This only stops once the content filter lets a word through.
+    pub(crate) fn generate_checked<F>(
+        &self,
+        tracker: Option<&UniquenessTracker>,
+        patience: usize,
+        mut generate: F,
+    ) -> Word
+    where
+        F: FnMut(usize) -> Word,
+    {
+        (0..)
+            .map(|attempt| (attempt, generate(attempt)))
+            .find(|(attempt, word)| self.accepts(tracker, word, *attempt, patience))
+            .map(|(_, word)| word)
+            .unwrap()
+    }
+
+    /// Generates words until one is acceptable. If that doesn't happen quickly, longer words
+    /// are tried, since short ones may simply have run out.
+    pub(crate) fn generate_acceptable_word(&self, rng: &mut Pcg64) -> Word {
+        self.generate_acceptable_word_with_lengths(rng, WORD_LENGTHS)
+    }
+
+    /// Like `generate_acceptable_word`, for words with `lengths` syllables.
+    pub(crate) fn generate_acceptable_word_with_lengths(
+        &self,
+        rng: &mut Pcg64,
+        lengths: &[(usize, i32)],
+    ) -> Word {
+        let longer: Vec<(usize, i32)> = lengths.iter().map(|(n, w)| (n + 2, *w)).collect();
+
+        // Once only the content filter is left, short words are the likeliest to get through
+        self.generate_checked(self.uniqueness.as_ref(), ATTEMPTS * 10, |attempt| {
+            if (ATTEMPTS..ATTEMPTS * 10).contains(&attempt) {
+                self.generate_word_with_lengths(rng, &longer)
+            } else {
+                self.generate_word_with_lengths(rng, lengths)
+            }
+        })
+    }
+
+    /// Compounds `left` and `right`. If the result isn't acceptable, tries keeping both words
+    /// whole, and then joining them with a linking syllable. If none of those get past the
+    /// content filter, a new word is generated instead.
+    pub(crate) fn generate_acceptable_compound(
+        &self,
+        rng: &mut Pcg64,
+        left: &Word,
+        right: &Word,
+    ) -> Word {
+        let compound = self.generate_compound(rng, left, right);
+        let join = |rng: &mut Pcg64, link: Option<Syllable>| {
+            let mut parts = left.parts.clone();
+            self.append_syllables(rng, &mut parts, link);
+            self.append_syllables(rng, &mut parts, right.parts.iter().cloned());
+            self.canonical_word(parts, compound.compound_rule)
+        };
+
+        let linked = ATTEMPTS + 2;
+        self.generate_checked(self.uniqueness.as_ref(), linked, |attempt| match attempt {
+            0 => compound.clone(),
+            1 => join(rng, None),
+            _ if attempt < linked * 2 => {
+                let link = self.syllable(rng, left.parts.last());
+                join(rng, Some(link))
+            }
+            _ => self.generate_acceptable_word(rng),
+        })
+    }
+}
+
+pub(crate) fn edit_distance(a: &[String], b: &[String]) -> usize {
+    let mut prev: Vec<usize> = (0..=b.len()).collect();
+
+    for (i, x) in a.iter().enumerate() {
+        let mut row = vec![i + 1];
+        for (j, y) in b.iter().enumerate() {
+            let substitution = prev[j] + usize::from(x != y);
+            row.push(substitution.min(prev[j + 1] + 1).min(row[j] + 1));
+        }
+        prev = row;
+    }
+
+    prev[b.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unique_words() {
+        let mut lang = SynthLang::new(12);
+        lang.set_uniqueness_tracker(Some(UniquenessTracker::new(Similarity::Spelling, 2)));
+
+        let words: Vec<String> = (0..300).map(|_| lang.word().to_string()).collect();
+        for (i, a) in words.iter().enumerate() {
+            for b in &words[..i] {
+                let a: Vec<String> = a.chars().map(String::from).collect();
+                let b: Vec<String> = b.chars().map(String::from).collect();
+                assert!(edit_distance(&a, &b) >= 2);
+            }
+        }
+        assert_eq!(lang.uniqueness_tracker().unwrap().len(), 300);
+    }
+
+    #[test]
+    fn unique_compounds() {
+        let mut lang = SynthLang::new(3);
+        lang.set_uniqueness_tracker(Some(UniquenessTracker::new(Similarity::Phonemes, 1)));
+
+        let left = lang.word();
+        let right = lang.word();
+        let first = lang.compound(&left, &right);
+        let second = lang.compound(&left, &right);
+        let third = lang.compound(&left, &right);
+
+        assert_ne!(first.to_string(), second.to_string());
+        assert_ne!(second.to_string(), third.to_string());
+        assert_ne!(first.to_string(), third.to_string());
+    }
+
+    #[test]
+    fn distances() {
+        let units = |s: &str| s.chars().map(String::from).collect::<Vec<_>>();
+        assert_eq!(edit_distance(&units("kitten"), &units("sitting")), 3);
+        assert_eq!(edit_distance(&units(""), &units("abc")), 3);
+        assert_eq!(edit_distance(&units("abc"), &units("abc")), 0);
+    }
+}
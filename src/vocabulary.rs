@@ -0,0 +1,396 @@
+//! A built-in core vocabulary: the Swadesh list plus fantasy staples, tagged by semantic field.
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SemanticField {
+    Pronoun,
+    Function,
+    Quantity,
+    Quality,
+    Color,
+    People,
+    Kinship,
+    Body,
+    Animal,
+    Plant,
+    Nature,
+    Place,
+    Time,
+    Action,
+    Food,
+    Artifact,
+    Society,
+    Magic,
+    War,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Concept {
+    pub key: &'static str,
+    pub field: SemanticField,
+    /// Basic concepts (the Swadesh list) prefer shorter words.
+    pub basic: bool,
+    /// Concepts that a language may choose to express as a compound of two others.
+    pub parts: Option<(&'static str, &'static str)>,
+}
+
+const fn basic(key: &'static str, field: SemanticField) -> Concept {
+    Concept {
+        key,
+        field,
+        basic: true,
+        parts: None,
+    }
+}
+
+const fn staple(key: &'static str, field: SemanticField) -> Concept {
+    Concept {
+        key,
+        field,
+        basic: false,
+        parts: None,
+    }
+}
+
+const fn compound(
+    key: &'static str,
+    field: SemanticField,
+    left: &'static str,
+    right: &'static str,
+) -> Concept {
+    Concept {
+        key,
+        field,
+        basic: false,
+        parts: Some((left, right)),
+    }
+}
+
+use SemanticField::*;
+
+pub const CORE_VOCABULARY: &[Concept] = &[
+    basic("i", Pronoun),
+    basic("you", Pronoun),
+    basic("he", Pronoun),
+    basic("she", Pronoun),
+    basic("it", Pronoun),
+    basic("we", Pronoun),
+    basic("you (plural)", Pronoun),
+    basic("they", Pronoun),
+    basic("this", Function),
+    basic("that", Function),
+    basic("here", Function),
+    basic("there", Function),
+    basic("who", Function),
+    basic("what", Function),
+    basic("where", Function),
+    basic("when", Function),
+    basic("how", Function),
+    basic("not", Function),
+    basic("at", Function),
+    basic("in", Function),
+    basic("with", Function),
+    basic("and", Function),
+    basic("if", Function),
+    basic("because", Function),
+    basic("all", Quantity),
+    basic("many", Quantity),
+    basic("some", Quantity),
+    basic("few", Quantity),
+    basic("other", Quantity),
+    basic("one", Quantity),
+    basic("two", Quantity),
+    basic("three", Quantity),
+    basic("four", Quantity),
+    basic("five", Quantity),
+    basic("big", Quality),
+    basic("long", Quality),
+    basic("wide", Quality),
+    basic("thick", Quality),
+    basic("heavy", Quality),
+    basic("small", Quality),
+    basic("short", Quality),
+    basic("narrow", Quality),
+    basic("thin", Quality),
+    basic("warm", Quality),
+    basic("cold", Quality),
+    basic("full", Quality),
+    basic("new", Quality),
+    basic("old", Quality),
+    basic("good", Quality),
+    basic("bad", Quality),
+    basic("rotten", Quality),
+    basic("dirty", Quality),
+    basic("straight", Quality),
+    basic("round", Quality),
+    basic("sharp", Quality),
+    basic("dull", Quality),
+    basic("smooth", Quality),
+    basic("wet", Quality),
+    basic("dry", Quality),
+    basic("correct", Quality),
+    basic("near", Quality),
+    basic("far", Quality),
+    basic("right", Quality),
+    basic("left", Quality),
+    basic("red", Color),
+    basic("green", Color),
+    basic("yellow", Color),
+    basic("white", Color),
+    basic("black", Color),
+    basic("woman", People),
+    basic("man", People),
+    basic("person", People),
+    basic("child", People),
+    basic("name", People),
+    basic("wife", Kinship),
+    basic("husband", Kinship),
+    basic("mother", Kinship),
+    basic("father", Kinship),
+    basic("animal", Animal),
+    basic("fish", Animal),
+    basic("bird", Animal),
+    basic("dog", Animal),
+    basic("louse", Animal),
+    basic("snake", Animal),
+    basic("worm", Animal),
+    basic("tree", Plant),
+    basic("forest", Plant),
+    basic("stick", Plant),
+    basic("fruit", Plant),
+    basic("seed", Plant),
+    basic("leaf", Plant),
+    basic("root", Plant),
+    basic("bark", Plant),
+    basic("flower", Plant),
+    basic("grass", Plant),
+    basic("rope", Artifact),
+    basic("skin", Body),
+    basic("meat", Body),
+    basic("blood", Body),
+    basic("bone", Body),
+    basic("fat", Body),
+    basic("egg", Body),
+    basic("horn", Body),
+    basic("tail", Body),
+    basic("feather", Body),
+    basic("hair", Body),
+    basic("head", Body),
+    basic("ear", Body),
+    basic("eye", Body),
+    basic("nose", Body),
+    basic("mouth", Body),
+    basic("tooth", Body),
+    basic("tongue", Body),
+    basic("fingernail", Body),
+    basic("foot", Body),
+    basic("leg", Body),
+    basic("knee", Body),
+    basic("hand", Body),
+    basic("wing", Body),
+    basic("belly", Body),
+    basic("guts", Body),
+    basic("neck", Body),
+    basic("back", Body),
+    basic("breast", Body),
+    basic("heart", Body),
+    basic("liver", Body),
+    basic("drink", Action),
+    basic("eat", Action),
+    basic("bite", Action),
+    basic("suck", Action),
+    basic("spit", Action),
+    basic("vomit", Action),
+    basic("blow", Action),
+    basic("breathe", Action),
+    basic("laugh", Action),
+    basic("see", Action),
+    basic("hear", Action),
+    basic("know", Action),
+    basic("think", Action),
+    basic("smell", Action),
+    basic("fear", Action),
+    basic("sleep", Action),
+    basic("live", Action),
+    basic("die", Action),
+    basic("kill", Action),
+    basic("fight", Action),
+    basic("hunt", Action),
+    basic("hit", Action),
+    basic("cut", Action),
+    basic("split", Action),
+    basic("stab", Action),
+    basic("scratch", Action),
+    basic("dig", Action),
+    basic("swim", Action),
+    basic("fly", Action),
+    basic("walk", Action),
+    basic("come", Action),
+    basic("lie", Action),
+    basic("sit", Action),
+    basic("stand", Action),
+    basic("turn", Action),
+    basic("fall", Action),
+    basic("give", Action),
+    basic("hold", Action),
+    basic("squeeze", Action),
+    basic("rub", Action),
+    basic("wash", Action),
+    basic("wipe", Action),
+    basic("pull", Action),
+    basic("push", Action),
+    basic("throw", Action),
+    basic("tie", Action),
+    basic("sew", Action),
+    basic("count", Action),
+    basic("say", Action),
+    basic("sing", Action),
+    basic("play", Action),
+    basic("float", Action),
+    basic("flow", Action),
+    basic("freeze", Action),
+    basic("swell", Action),
+    basic("burn", Action),
+    basic("sun", Nature),
+    basic("moon", Nature),
+    basic("star", Nature),
+    basic("water", Nature),
+    basic("rain", Nature),
+    basic("salt", Nature),
+    basic("stone", Nature),
+    basic("sand", Nature),
+    basic("dust", Nature),
+    basic("earth", Nature),
+    basic("cloud", Nature),
+    basic("fog", Nature),
+    basic("sky", Nature),
+    basic("wind", Nature),
+    basic("snow", Nature),
+    basic("ice", Nature),
+    basic("smoke", Nature),
+    basic("fire", Nature),
+    basic("ash", Nature),
+    basic("river", Place),
+    basic("lake", Place),
+    basic("sea", Place),
+    basic("road", Place),
+    basic("mountain", Place),
+    basic("night", Time),
+    basic("day", Time),
+    basic("year", Time),
+    // Fantasy staples
+    staple("blue", Color),
+    staple("gray", Color),
+    staple("brown", Color),
+    staple("gold", Color),
+    staple("silver", Color),
+    staple("iron", Artifact),
+    staple("son", Kinship),
+    staple("daughter", Kinship),
+    staple("brother", Kinship),
+    staple("sister", Kinship),
+    staple("friend", People),
+    staple("enemy", People),
+    staple("people", People),
+    staple("horse", Animal),
+    staple("wolf", Animal),
+    staple("bear", Animal),
+    staple("eagle", Animal),
+    staple("raven", Animal),
+    staple("hill", Place),
+    staple("valley", Place),
+    staple("island", Place),
+    staple("cave", Place),
+    staple("ford", Place),
+    staple("village", Place),
+    staple("house", Artifact),
+    staple("bridge", Artifact),
+    staple("gate", Artifact),
+    staple("wall", Artifact),
+    staple("knife", Artifact),
+    staple("shield", War),
+    staple("spear", War),
+    staple("bow", War),
+    staple("arrow", War),
+    staple("axe", War),
+    staple("war", War),
+    staple("lord", Society),
+    staple("lady", Society),
+    staple("law", Society),
+    staple("oath", Society),
+    staple("gift", Society),
+    staple("god", Magic),
+    staple("spirit", Magic),
+    staple("magic", Magic),
+    staple("rune", Magic),
+    staple("curse", Magic),
+    staple("light", Nature),
+    staple("shadow", Nature),
+    staple("storm", Nature),
+    staple("thunder", Nature),
+    staple("winter", Time),
+    staple("summer", Time),
+    staple("dawn", Time),
+    staple("north", Place),
+    staple("south", Place),
+    staple("east", Place),
+    staple("west", Place),
+    staple("bread", Food),
+    staple("wine", Food),
+    staple("feast", Food),
+    staple("holy", Quality),
+    staple("wise", Quality),
+    staple("strong", Quality),
+    staple("brave", Quality),
+    staple("ancient", Quality),
+    compound("dragon", Magic, "fire", "snake"),
+    compound("king", Society, "big", "man"),
+    compound("queen", Society, "big", "woman"),
+    compound("castle", War, "stone", "house"),
+    compound("city", Place, "big", "village"),
+    compound("sword", War, "long", "knife"),
+    compound("wizard", Magic, "magic", "man"),
+    compound("witch", Magic, "magic", "woman"),
+    compound("temple", Society, "god", "house"),
+    compound("warrior", War, "fight", "man"),
+    compound("smith", Society, "iron", "man"),
+    compound("crown", Society, "gold", "head"),
+    compound("ghost", Magic, "dead", "spirit"),
+    compound("dead", Quality, "die", "man"),
+    compound("ship", Artifact, "sea", "house"),
+    compound("treasure", Artifact, "gold", "stone"),
+    compound("death", Magic, "die", "night"),
+    compound("army", War, "many", "warrior"),
+    compound("hero", War, "brave", "man"),
+];
+
+/// Looks up a concept in the core vocabulary.
+#[must_use]
+pub fn concept(key: &str) -> Option<&'static Concept> {
+    CORE_VOCABULARY.iter().find(|c| c.key == key)
+}
+
+/// All concepts in a semantic field.
+pub fn field(field: SemanticField) -> impl Iterator<Item = &'static Concept> {
+    CORE_VOCABULARY.iter().filter(move |c| c.field == field)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn keys_are_unique_and_parts_exist() {
+        for (i, c) in CORE_VOCABULARY.iter().enumerate() {
+            assert!(
+                CORE_VOCABULARY[..i].iter().all(|other| other.key != c.key),
+                "duplicate {}",
+                c.key
+            );
+
+            if let Some((left, right)) = c.parts {
+                assert!(concept(left).is_some(), "{} missing", left);
+                assert!(concept(right).is_some(), "{} missing", right);
+            }
+        }
+    }
+}